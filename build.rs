@@ -0,0 +1,33 @@
+//! Generates the registry of days from the `dayN` dependencies of the runner,
+//! so adding a day only means adding its crate to `Cargo.toml`.
+
+use std::fmt::Write;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+
+    let manifest = std::fs::read_to_string("Cargo.toml").expect("Failed to read Cargo.toml");
+    let manifest: toml::Table = manifest.parse().expect("Cargo.toml is not valid TOML");
+
+    let mut days: Vec<u32> = manifest
+        .get("dependencies")
+        .and_then(|deps| deps.as_table())
+        .map(|deps| {
+            deps.keys()
+                .filter_map(|name| name.strip_prefix("day")?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    days.sort();
+
+    let mut registry = String::from("pub static DAYS: &[utils::Day] = &[\n");
+    for day in days {
+        writeln!(registry, "    day{day}::DAY,").unwrap();
+    }
+    registry.push_str("];\n");
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("days.rs"), registry)
+        .expect("Failed to write the registry");
+}
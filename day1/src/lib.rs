@@ -1,22 +1,25 @@
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
 use std::str::FromStr;
-use utils::Problem;
+use utils::{lines_of_str, Answer, ParseError, Problem};
 
 pub struct Day1();
 
 impl Problem for Day1 {
-    fn get_part1(&self) -> fn(bool) -> () {
-        part1::run
+    type Parsed = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(lines_of_str(input))
+    }
+
+    fn part1(&self, parsed: &Self::Parsed) -> Answer {
+        part1::solve(parsed).into()
     }
 
-    fn get_part2(&self) -> fn(bool) -> () {
-        part2::run
+    fn part2(&self, parsed: &Self::Parsed) -> Answer {
+        part2::solve(parsed).into()
     }
 }
 
-pub const DAY_1: Day1 = Day1();
+utils::register_day!(1, Day1());
 
 mod part1 {
     use super::*;
@@ -41,31 +44,12 @@ mod part1 {
         assert_eq!(12, calibration_of_line("1abc2"));
     }
 
-    #[allow(dead_code)]
-    pub fn run(debug: bool) {
-        let filename = if debug {
-            "part1.example"
-        } else {
-            "part1.puzzle"
-        };
-        let mut file = File::open(Path::new(filename)).expect("Missing part1.puzzle file");
-        let mut buff = String::new();
-
-        file.read_to_string(&mut buff)
-            .expect("Failed to read the file");
-
-        let total = buff
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(calibration_of_line)
-            .sum::<u32>();
-
-        println!("The total is {total}");
+    pub fn solve(lines: &[String]) -> u32 {
+        lines.iter().map(|line| calibration_of_line(line)).sum()
     }
 }
 
 mod part2 {
-    use super::*;
     use crate::part1::calibration_of_line;
     use std::collections::HashMap;
 
@@ -121,35 +105,19 @@ mod part2 {
             }
         }
 
-        #[cfg(debug_assertions)]
-        {
-            let val = calibration_of_line(&rewritten);
-            println!("{source} => {rewritten} ({val})");
-        }
+        utils::debug!(
+            "{source} => {rewritten} ({0})",
+            calibration_of_line(&rewritten)
+        );
 
         rewritten
     }
 
-    #[allow(dead_code)]
-    pub fn run(debug: bool) {
-        let filename = if debug {
-            "part2.example"
-        } else {
-            "part2.puzzle"
-        };
-        let mut file = File::open(Path::new(filename)).expect("Missing part2.puzzle file");
-        let mut buff = String::new();
-
-        file.read_to_string(&mut buff)
-            .expect("Failed to read the file");
-
-        let total = buff
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(replace_words_to_digits)
+    pub fn solve(lines: &[String]) -> u32 {
+        lines
+            .iter()
+            .map(|line| replace_words_to_digits(line))
             .map(|line| calibration_of_line(&line))
-            .sum::<u32>();
-
-        println!("The total is {total}");
+            .sum()
     }
 }
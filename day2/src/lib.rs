@@ -1,22 +1,28 @@
-use regex::Regex;
 use std::cmp::max;
-use std::ops::Deref;
 use std::str::FromStr;
-use utils::{lines_of_file, Problem};
+use utils::{numbered_lines, scan, Answer, ParseError, Problem};
 
 pub struct Day2();
 
 impl Problem for Day2 {
-    fn get_part1(&self) -> fn(bool) -> () {
-        part1::run
+    type Parsed = Vec<Game>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        numbered_lines(input)
+            .map(|(n, line)| Game::from_str(line).map_err(|e| e.on_line(n)))
+            .collect()
+    }
+
+    fn part1(&self, parsed: &Self::Parsed) -> Answer {
+        part1::solve(parsed).into()
     }
 
-    fn get_part2(&self) -> fn(bool) -> () {
-        part2::run
+    fn part2(&self, parsed: &Self::Parsed) -> Answer {
+        part2::solve(parsed).into()
     }
 }
 
-pub const DAY_2: Day2 = Day2();
+utils::register_day!(2, Day2());
 
 #[derive(Debug, PartialEq)]
 struct Pick {
@@ -32,23 +38,24 @@ impl Pick {
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     id: u32,
     picks: Vec<Pick>,
 }
 
 impl FromStr for Game {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let be = s.find(':').expect("No beginning of rounds");
+        let be = s
+            .find(':')
+            .ok_or_else(|| ParseError::in_line(s, s, "`Game N:` before the rounds"))?;
 
         // Get the game id
-        let id: String = s[..be].chars().filter(|c| c.is_numeric()).collect();
-        let id = u32::from_str(&id).unwrap_or_else(|_| panic!("Invalid id: got {s}"));
+        let id_part = s[..be].trim_start_matches("Game").trim();
+        let id = scan::int(id_part).map_err(|e| e.in_line(s))?;
 
-        let splits = s[be..].split(';');
-        let color_regex = Regex::new(r"(\d+) (red|green|blue)").unwrap();
+        let splits = s[be + 1..].split(';');
 
         let mut picks: Vec<Pick> = vec![];
 
@@ -57,21 +64,19 @@ impl FromStr for Game {
             let mut green = 0_u32;
             let mut blue = 0_u32;
 
-            let haystack = String::from(split).replace(':', "");
-
-            color_regex
-                .captures_iter(haystack.trim())
-                .for_each(|capture| {
-                    let count = u32::from_str(capture.get(1).unwrap().as_str()).unwrap();
-                    let color = capture.get(2).unwrap().as_str();
+            for cubes in split.split(',').map(str::trim) {
+                let (count, color) = cubes.split_once(' ').ok_or_else(|| {
+                    ParseError::in_line(s, cubes, "a number of cubes and a color")
+                })?;
+                let count = scan::int(count).map_err(|e| e.in_line(s))?;
 
-                    match color.to_ascii_lowercase().deref() {
-                        "red" => red = count,
-                        "blue" => blue = count,
-                        "green" => green = count,
-                        other => panic!("Unknown color: {0}!", other),
-                    }
-                });
+                match color {
+                    "red" => red = count,
+                    "blue" => blue = count,
+                    "green" => green = count,
+                    other => return Err(ParseError::in_line(s, other, "a color")),
+                }
+            }
 
             picks.push(Pick { red, green, blue })
         }
@@ -122,49 +127,42 @@ fn test_parse_game() {
     );
 }
 
-fn games(debug: bool) -> Vec<Game> {
-    let file_name = match debug {
-        true => "files/day2.example",
-        false => "files/day2.puzzle",
-    };
-
-    let lines = lines_of_file(file_name).unwrap_or_else(|_| panic!("File not found: {file_name}"));
-    lines
-        .into_iter()
-        .filter_map(|line| Game::from_str(&line).ok())
-        .collect()
+#[test]
+fn test_parse_bad_game() {
+    let e = Game::from_str("Game x1: 3 blue").unwrap_err();
+    assert_eq!((1, 6), (e.line, e.column));
+    assert_eq!("x1", e.text);
+
+    let e = Game::from_str("Game 1 3 blue").unwrap_err();
+    assert_eq!("`Game N:` before the rounds", e.expected);
+
+    let e = Game::from_str("Game 1: 3 blue, 4 purple").unwrap_err();
+    assert_eq!((1, 19), (e.line, e.column));
+    assert_eq!("a color", e.expected);
+
+    let e = Game::from_str("Game 1: 3 blue, 99999999999 red").unwrap_err();
+    assert_eq!("a number that fits in u32", e.expected);
 }
 
 mod part1 {
     use super::*;
 
-    pub fn run(debug: bool) {
-        println!("Day 2 part 1");
-
-        let games = games(debug);
-
-        let valid_sum: u32 = games
+    pub fn solve(games: &[Game]) -> u32 {
+        games
             .iter()
             .filter(|game| game.is_viable(12, 13, 14))
             .map(|game| game.id)
-            .sum();
-
-        println!("The sum of the ids of valid games is {valid_sum}");
+            .sum()
     }
 }
 
 mod part2 {
     use super::*;
 
-    pub fn run(debug: bool) {
-        println!("Day 2 part 2");
-
-        let games = games(debug);
-        let sum_of_power: u32 = games
+    pub fn solve(games: &[Game]) -> u32 {
+        games
             .iter()
             .map(|game| game.smallest_possible().power())
-            .sum();
-
-        println!("The sum of power is {sum_of_power}.");
+            .sum()
     }
 }
@@ -1,492 +1,210 @@
-use std::cmp::{max, min};
-use std::fmt::{Debug, Display, Formatter, Write};
-use std::fs::File;
-use std::path::Path;
-use std::str::FromStr;
-use utils::{str_of_file, Problem};
+use utils::{scan, span, Answer, Grid, ParseError, Problem};
 
 pub struct Day3();
 
 const SYMBOLS: &str = "()+-#*&$@=/?!%";
 
 impl Problem for Day3 {
-    fn get_part1(&self) -> fn(bool) -> () {
-        part1::run
-    }
+    type Parsed = Grid<char>;
 
-    fn get_part2(&self) -> fn(bool) -> () {
-        part2::run
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        input.parse()
     }
-}
-
-pub const DAY_3: Day3 = Day3();
 
-fn filename(debug: bool) -> &'static str {
-    match debug {
-        true => "files/day3.example",
-        false => "files/day3.puzzle",
+    fn part1(&self, parsed: &Self::Parsed) -> Answer {
+        part1::solve(parsed).into()
     }
-}
 
-struct Matrix<T: Default + Copy + Clone + Debug> {
-    dims: (usize, usize),
-    vals: Vec<T>,
+    fn part2(&self, parsed: &Self::Parsed) -> Answer {
+        part2::solve(parsed).into()
+    }
 }
 
-impl FromStr for Matrix<char> {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<_> = s.lines().collect();
-        let n = lines.len();
-        let m = lines.iter().next().unwrap().len();
+utils::register_day!(3, Day3());
 
-        let mut mat = Self::new(n, m);
+type Position = (usize, usize);
 
-        for (row, line) in lines.iter().enumerate() {
-            for (col, char) in line.chars().enumerate() {
-                mat.set(char, row, col)
-            }
-        }
-
-        Ok(mat)
-    }
+struct NumberPair {
+    number1: u32,
+    number2: u32,
 }
 
-impl<T: Default + Copy + Clone + Debug> Matrix<T> {
-    pub fn new(rows: usize, cols: usize) -> Self {
-        let vals = vec![T::default(); rows * cols];
-        Matrix {
-            dims: (rows, cols),
-            vals,
+/// Replace the characters that are not marked in `keep`.
+fn mask(text: &mut Grid<char>, replacement: char, keep: &Grid<bool>) {
+    for position in text.positions() {
+        if !keep[position] {
+            text[position] = replacement;
         }
     }
+}
 
-    pub fn get(&self, row: usize, col: usize) -> T {
-        let (_, cols) = self.dims;
-        self.vals[row * cols + col]
-    }
-
-    pub fn set(&mut self, val: T, row: usize, col: usize) {
-        let (_, cols) = self.dims;
-        self.vals[row * cols + col] = val
-    }
-
-    pub fn dims(&self) -> (usize, usize) {
-        self.dims
+/// Mark the cells that are marked in either grid.
+fn or(marks: &mut Grid<bool>, other: &Grid<bool>) {
+    for position in marks.positions() {
+        marks[position] |= other[position];
     }
 }
 
-impl<T: Default + Copy + Clone + Debug + Display> Debug for Matrix<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let (rows, cols) = self.dims;
-        let mut buff = String::new();
+/// Extend the marked cells left and right along their row, for as long as
+/// the cells next to them are `within`.
+fn spread_along_rows(marks: &mut Grid<bool>, within: impl Fn(Position) -> bool) {
+    let cols = marks.cols();
 
-        f.write_char('\n')?;
-        for row in 0..rows {
-            for col in 0..cols {
-                buff.write_str(&format!("{0}", self.get(row, col)))?;
-            }
-            buff.write_char('\n')?;
+    for (row, col) in marks.positions() {
+        if !marks[(row, col)] {
+            continue;
         }
 
-        f.write_str(&buff)
-    }
-}
+        let mut start = col;
+        while start > 0 && within((row, start - 1)) {
+            start -= 1;
+            marks[(row, start)] = true;
+        }
 
-impl<T: Clone + Copy + Default + Debug> Clone for Matrix<T> {
-    fn clone(&self) -> Self {
-        Matrix {
-            dims: self.dims,
-            vals: self.vals.clone(),
+        let mut end = col + 1;
+        while end < cols && within((row, end)) {
+            marks[(row, end)] = true;
+            end += 1;
         }
     }
 }
 
-struct NumberPair {
-    number1: u32,
-    number2: u32,
+fn any_adjacent_symbol(position: Position, text: &Grid<char>) -> bool {
+    text.neighbours8(position)
+        .any(|neighbour| SYMBOLS.contains(text[neighbour]))
 }
 
-impl Matrix<char> {
-    pub fn mask(&mut self, replacement: char, other: &Matrix<bool>) {
-        self.vals
-            .iter_mut()
-            .zip(&other.vals)
-            .for_each(|(mine, theirs)| {
-                if !*theirs {
-                    *mine = replacement;
-                }
-            });
-    }
+/// Mark the digits of the numbers with a symbol next to them.
+fn check_numbers(text: &Grid<char>) -> Grid<bool> {
+    let _s = span("check_numbers");
+    let mut numbers = text.map(|_| false);
 
-    pub fn extract_pairs(&self, _m: &Matrix<bool>) -> Vec<NumberPair> {
-        let mut copy = self.clone();
-        let mut vec = vec![];
-        let (rows, cols) = self.dims;
-
-        for row in 0..rows {
-            for col in 0..cols {
-                let sym = copy.get(row, col);
-                if sym == '*' {
-                    let mut num1: Option<u32> = None;
-                    let mut num2: Option<u32> = None;
-
-                    for i in -1..=1 {
-                        for j in -1..=1 {
-                            if i == 0 && j == 0 {
-                                continue;
-                            }
-
-                            let row = row as isize + i;
-                            let col = col as isize + j;
-
-                            if row < 0 || row as usize > rows || col < 0 || col as usize > rows {
-                                continue;
-                            }
-
-                            let row = row as usize;
-                            let col = col as usize;
-
-                            let mut s = col;
-                            let mut e = col;
-
-                            // Go backwards
-                            while s != 0 && copy.get(row, s - 1).is_numeric() {
-                                s -= 1;
-                            }
-
-                            // Go forward
-                            while e < cols && copy.get(row, e + 1).is_numeric() {
-                                e += 1;
-                            }
-
-                            let range = row * cols + s..=row * cols + e;
-                            let slice = &copy.vals[range.clone()];
-
-                            let s: String = slice.iter().collect();
-                            let number = match u32::from_str(&s) {
-                                Ok(num) => num,
-                                Err(_) => continue,
-                            };
-                            copy.vals[range.clone()].iter_mut().for_each(|c| *c = '.');
-
-                            if num1.is_none() {
-                                num1 = Some(number);
-                            } else {
-                                num2 = Some(number);
-                            }
-                        }
-                    }
-
-                    if num1.is_none() {
-                        panic!("Error: found no number around a star");
-                    }
-
-                    if num2.is_none() {
-                        dbg!(row);
-                        dbg!(col);
-                        dbg!(&num1);
-                        panic!("Error: found the wrong number around a start");
-                    }
-
-                    vec.push(NumberPair {
-                        number1: num1.unwrap(),
-                        number2: num2.unwrap(),
-                    })
-                }
-            }
+    for (position, c) in text.iter() {
+        if c.is_numeric() {
+            numbers[position] = any_adjacent_symbol(position, text);
         }
-
-        vec
     }
 
-    pub fn extract_numbers(&self) -> Vec<u32> {
-        let mut vec = vec![];
-
-        let (rows, cols) = self.dims;
-
-        let mut buff = String::new();
-        for row in 0..rows {
-            for col in 0..cols {
-                let c = self.get(row, col);
-                if c.is_numeric() {
-                    buff.push(c);
-                } else if buff.len() > 0 {
-                    vec.push(u32::from_str(&buff).unwrap());
-                    buff.clear();
-                }
-            }
-
-            if buff.len() > 0 {
-                vec.push(u32::from_str(&buff).unwrap());
-                buff.clear();
-            }
-        }
-
-        vec
-    }
+    spread_along_rows(&mut numbers, |position| text[position].is_numeric());
+    numbers
 }
 
-#[derive(Copy, Clone)]
-struct Point {
-    row: usize,
-    col: usize,
-}
-
-impl Matrix<bool> {
-    fn any_adjacent_ok(row: usize, col: usize, matrix: &Matrix<char>) -> bool {
-        let (rows, cols) = matrix.dims;
-
-        let mut any_ok = false;
-
-        for i in -1..=1 {
-            for j in -1..=1 {
-                let row = (row as isize) + i;
-                let col = (col as isize) + j;
-
-                let row_oob = row < 0 || (row as usize) >= rows;
-                let col_oob = col < 0 || (col as usize) >= cols;
-
-                if row_oob || col_oob || (i == 0 && j == 0) {
-                    continue;
-                }
+/// Mark the stars with exactly two of the marked numbers around them.
+fn find_gears(numbers: &Grid<bool>, text: &Grid<char>) -> Grid<bool> {
+    let _s = span("find_gears");
+    let mut gears = text.map(|_| false);
 
-                let sym = matrix.get(row as usize, col as usize);
-
-                any_ok |= SYMBOLS.contains(sym);
-
-                if any_ok {
-                    break;
-                }
-            }
+    for (position, c) in text.iter() {
+        if *c != '*' {
+            continue;
         }
 
-        any_ok
-    }
-
-    pub fn or(&mut self, other: &Matrix<bool>) {
-        self.vals
-            .iter_mut()
-            .zip(&other.vals)
-            .for_each(|(mine, theirs)| {
-                *mine = *mine || *theirs;
-            })
-    }
+        let mut points: Vec<Position> = vec![];
+        for (row, col) in text.neighbours8(position) {
+            let any_neigh_pt = points.iter().any(|&(p_row, p_col)| {
+                // To determine if there is a neighbouring point, we check
+                // if we have one on the same row that is accessible directly
+                // through a path without symbols (row-wise)
+                p_row == row
+                    && (col.min(p_col) + 1..col.max(p_col)).all(|col| text[(row, col)].is_numeric())
+            });
 
-    pub fn find_gears(&mut self, number_matrix: &Matrix<bool>, sym_matrix: &Matrix<char>) {
-        let (rows, cols) = self.dims;
-
-        let mut points: Vec<Point> = vec![];
-
-        for row in 0..rows {
-            for col in 0..cols {
-                // Is it a gear?
-                let sym = sym_matrix.get(row, col);
-                if sym != '*' {
-                    continue;
-                }
-
-                // Check if two numbers next to it
-                for i in -1..=1 {
-                    for j in -1..=1 {
-                        let col = col as isize + i;
-                        let row = row as isize + j;
-
-                        // check oob
-                        if col < 0 || row < 0 || col as usize >= cols || row as usize >= rows {
-                            continue;
-                        }
-
-                        let row = row as usize;
-                        let col = col as usize;
-
-                        let has_num = number_matrix.get(row, col);
-                        let any_neigh_pt = points.iter().any(|p| {
-                            // To determine if there is a neighbouring point, we check
-                            // if we have one on the same row that is accessible directly
-                            // through a path without symbols (row-wise)
-
-                            if p.row != row {
-                                return false;
-                            }
-
-                            let min_col = min(col, p.col) + 1;
-                            let max_col = max(col, p.col);
-
-                            for col in min_col..max_col {
-                                // If it's not a symbol, it's part of a number
-                                // we've counted already
-                                if !sym_matrix.get(row, col).is_numeric() {
-                                    return false;
-                                }
-                            }
-
-                            return true;
-                        });
-
-                        if has_num && !any_neigh_pt {
-                            points.push(Point { row, col })
-                        }
-
-                        if points.len() == 2 {
-                            break;
-                        }
-                    }
-                }
-
-                self.set(points.len() == 2, row, col);
-                points.clear();
+            if numbers[(row, col)] && !any_neigh_pt {
+                points.push((row, col));
             }
         }
-    }
 
-    pub fn keep_gear_adjacent(&mut self, gear_matrix: &Matrix<bool>) {
-        let (rows, cols) = self.dims;
-        let old = self.clone();
-
-        for row in 0..rows {
-            for col in 0..cols {
-                let mut any = false;
+        gears[position] = points.len() == 2;
+    }
 
-                for i in -1..=1 {
-                    for j in -1..=1 {
-                        let row = (row as isize) + i;
-                        let col = (col as isize) + j;
+    gears
+}
 
-                        let row_oob = row < 0 || (row as usize) >= rows;
-                        let col_oob = col < 0 || (col as usize) >= cols;
+/// Only keep the numbers with a gear next to them.
+fn keep_gear_adjacent(numbers: &mut Grid<bool>, gears: &Grid<bool>) {
+    let old = numbers.clone();
 
-                        if row_oob || col_oob || (i == 0 && j == 0) {
-                            continue;
-                        }
+    for position in numbers.positions() {
+        let any = gears
+            .neighbours8(position)
+            .any(|neighbour| gears[neighbour]);
+        numbers[position] = any && old[position];
+    }
 
-                        let row = row as usize;
-                        let col = col as usize;
+    spread_along_rows(numbers, |position| old[position]);
+}
 
-                        any |= gear_matrix.get(row, col);
-                    }
-                }
+/// Numbers around every star, each number counted once.
+fn extract_pairs(text: &Grid<char>) -> Vec<NumberPair> {
+    let _s = span("extract_pairs");
+    let mut copy = text.clone();
+    let mut vec = vec![];
+    let cols = text.cols();
 
-                self.set(any && old.get(row, col), row, col);
-            }
+    for star in text.positions() {
+        if copy[star] != '*' {
+            continue;
         }
 
-        // Sideway scan left & right to complete the number
-        for row in 0..rows {
-            for col in 0..cols {
-                if !self.get(row, col) {
-                    continue;
-                }
-
-                let i = col;
-                for j in 0..cols {
-                    // Check oob on the right
-                    if i + j >= cols {
-                        break;
-                    }
-
-                    let candidate = old.get(row, i + j);
-                    if candidate {
-                        self.set(true, row, i + j);
-                    } else {
-                        break;
-                    }
-                }
-
-                let i = col as isize;
-                for j in 0..(cols as isize) {
-                    // Check oob on the right
-                    if i - j < 0 {
-                        break;
-                    }
-
-                    let candidate = old.get(row, (i - j) as usize);
-                    if candidate {
-                        self.set(true, row, (i - j) as usize);
-                    } else {
-                        break;
-                    }
-                }
+        let mut numbers = vec![];
+        for (row, col) in text.neighbours8(star) {
+            if !copy[(row, col)].is_numeric() {
+                continue;
             }
-        }
-    }
 
-    pub fn sum(&self) -> u32 {
-        self.vals.iter().map(|v| *v as u32).sum()
-    }
+            let mut start = col;
+            while start > 0 && copy[(row, start - 1)].is_numeric() {
+                start -= 1;
+            }
 
-    pub fn check_numbers(&mut self, text_matrix: &Matrix<char>) {
-        let (rows, cols) = self.dims;
+            let mut end = col + 1;
+            while end < cols && copy[(row, end)].is_numeric() {
+                end += 1;
+            }
 
-        for row in 0..rows {
-            for col in 0..cols {
-                if text_matrix.get(row, col).is_numeric() {
-                    self.set(Self::any_adjacent_ok(row, col, text_matrix), row, col)
-                }
+            let digits: String = copy.row(row)[start..end].iter().collect();
+            numbers.push(scan::int::<u32>(&digits).expect("Part number too large"));
+            for col in start..end {
+                copy[(row, col)] = '.';
             }
         }
 
-        for row in 0..rows {
-            for col in 0..cols {
-                let val = self.get(row, col);
-
-                // Sideway scan left & right to complete the number
-                if !val {
-                    continue;
-                }
-
-                let i = col;
-                for j in 0..rows {
-                    // Check oob on the right
-                    if i + j >= cols {
-                        break;
-                    }
-
-                    let candidate = text_matrix.get(row, i + j);
-                    if candidate.is_numeric() {
-                        self.set(true, row, i + j);
-                    } else {
-                        break;
-                    }
-                }
-
-                let i = col as isize;
-                for j in 0..(rows as isize) {
-                    // Check oob on the right
-                    if i - j < 0 {
-                        break;
-                    }
-
-                    let candidate = text_matrix.get(row, (i - j) as usize);
-                    if candidate.is_numeric() {
-                        self.set(true, row, (i - j) as usize);
-                    } else {
-                        break;
-                    }
-                }
+        let (row, col) = star;
+        match numbers[..] {
+            [number1, number2] => vec.push(NumberPair { number1, number2 }),
+            [] => panic!("Error: found no number around a star"),
+            [number] => {
+                panic!("Error: found a single number ({number}) around the star at {row}, {col}")
             }
+            _ => panic!(
+                "Error: found {0} numbers around the star at {row}, {col}",
+                numbers.len()
+            ),
         }
     }
+
+    vec
 }
 
-#[cfg(test)]
-mod matrix_tests {
-    use crate::Matrix;
+fn extract_numbers(text: &Grid<char>) -> Vec<u32> {
+    let mut vec = vec![];
 
-    #[test]
-    pub fn test_access() {
-        let mut matrix = Matrix::new(10, 10);
-        assert_eq!('\0', matrix.get(0, 0));
-        matrix.set('v', 9, 0);
-        assert_eq!('v', matrix.get(9, 0));
-        dbg!(matrix.vals);
+    for row in text.iter_rows() {
+        let line: String = row.iter().collect();
+        vec.extend(scan::ints::<u32>(&line).expect("Part number too large"));
     }
+
+    vec
 }
 
 #[cfg(test)]
 mod part1_tests {
-    use crate::part1::solve;
+    use std::str::FromStr;
+    use utils::Grid;
+
+    fn solve(s: &str) -> u32 {
+        crate::part1::solve(&Grid::from_str(s).unwrap())
+    }
 
     #[test]
     fn case1() {
@@ -559,85 +277,94 @@ $..
         let s = r#"11.$."#;
         assert_eq!(0, solve(s));
     }
+
+    #[test]
+    fn case9() {
+        // Longer than the grid is high
+        let s = r#"1234$"#;
+        assert_eq!(1234, solve(s));
+    }
 }
 
-mod part2 {
-    use super::*;
-    use std::io::Write;
+#[cfg(test)]
+mod part2_tests {
+    use std::str::FromStr;
+    use utils::Grid;
 
-    fn dump_to_file<T: Debug + Display + Copy + Default>(m: &Matrix<T>, file: &str) {
-        let mut s = String::new();
-        {
-            use std::fmt::Write;
-            s.write_fmt(format_args!("{:#?}", &m)).unwrap();
-        }
-        s = s.replace("false", ".").replace("true", "t");
+    fn solve(s: &str) -> u32 {
+        crate::part2::solve(&Grid::from_str(s).unwrap())
+    }
 
-        File::create(Path::new(file))
-            .unwrap()
-            .write(s.as_bytes())
-            .unwrap();
+    #[test]
+    fn case1() {
+        let s = r#"467..114..
+...*......
+..35..633."#;
+        assert_eq!(467 * 35, solve(s));
     }
 
-    pub fn solve(s: &str) -> u32 {
-        let mut text_matrix = Matrix::from_str(s).unwrap();
-        let (rows, cols) = text_matrix.dims();
+    #[test]
+    fn case2() {
+        // Gear in a column past the number of rows
+        let s = r#"..........
+.......2*3"#;
+        assert_eq!(6, solve(s));
+    }
 
-        let mut boolean_matrix: Matrix<bool> = Matrix::new(rows, cols);
-        boolean_matrix.check_numbers(&text_matrix);
+    #[test]
+    fn case3() {
+        // Three numbers around a star don't make a gear
+        let s = r#"1.2
+.*.
+..3"#;
+        assert_eq!(0, solve(s));
+    }
+}
 
-        let mut gear_matrix: Matrix<bool> = Matrix::new(rows, cols);
-        gear_matrix.find_gears(&boolean_matrix, &text_matrix);
+mod part2 {
+    use super::*;
 
-        dump_to_file(&gear_matrix, "temp/gear_matrix.txt");
+    pub fn solve(grid: &Grid<char>) -> u32 {
+        let mut text = grid.clone();
 
-        println!("Found {0} gears.", gear_matrix.sum());
+        let mut numbers = check_numbers(&text);
+        let gears = find_gears(&numbers, &text);
 
-        boolean_matrix.keep_gear_adjacent(&gear_matrix);
+        utils::debug!(
+            "Found {0} gears.",
+            gears.iter().filter(|(_, g)| **g).count()
+        );
+        utils::trace!("Gears:\n{0}", gears.map(|&g| if g { 't' } else { '.' }));
 
-        // Remove the non-number stuff
-        boolean_matrix.or(&gear_matrix);
-        text_matrix.mask('.', &boolean_matrix);
+        keep_gear_adjacent(&mut numbers, &gears);
 
-        dump_to_file(&text_matrix, "temp/text_matrix.txt");
+        // Remove the non-number stuff
+        or(&mut numbers, &gears);
+        mask(&mut text, '.', &numbers);
+        utils::trace!("Numbers next to gears:\n{text}");
 
-        let pairs = text_matrix.extract_pairs(&boolean_matrix);
+        let pairs = extract_pairs(&text);
 
         pairs.iter().for_each(|pair| {
             let number1 = pair.number1;
             let number2 = pair.number2;
 
-            println!("Gear: {number1}*{number2}");
+            utils::trace!("Gear: {number1}*{number2}");
         });
 
         pairs.iter().map(|pair| pair.number1 * pair.number2).sum()
     }
-
-    pub fn run(debug: bool) {
-        let s = &str_of_file(filename(debug)).unwrap();
-        let result = solve(s);
-        println!("Gear ratio is: {result}")
-    }
 }
 
 mod part1 {
     use super::*;
 
-    pub fn solve(s: &str) -> u32 {
-        let mut text_matrix = Matrix::from_str(s).unwrap();
-        let (rows, cols) = text_matrix.dims();
+    pub fn solve(grid: &Grid<char>) -> u32 {
+        let mut text = grid.clone();
 
-        let mut boolean_matrix: Matrix<bool> = Matrix::new(rows, cols);
-        boolean_matrix.check_numbers(&text_matrix);
-        text_matrix.mask('.', &boolean_matrix);
-
-        let numbers = text_matrix.extract_numbers();
-        numbers.into_iter().sum::<u32>()
-    }
+        let numbers = check_numbers(&text);
+        mask(&mut text, '.', &numbers);
 
-    pub fn run(debug: bool) {
-        let s = &str_of_file(filename(debug)).unwrap();
-        let result = solve(s);
-        println!("Sum is: {result}")
+        extract_numbers(&text).into_iter().sum::<u32>()
     }
 }
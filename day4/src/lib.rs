@@ -1,34 +1,34 @@
 use regex::{Match, Regex};
 use std::collections::HashMap;
-use std::fmt::{Display, Formatter, Write};
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
-use std::usize;
-use utils::lines_of_file;
-use utils::Problem;
+use utils::numbered_lines;
+use utils::{scan, Answer, ParseError, Problem};
 
 pub struct Day4();
 
 impl Problem for Day4 {
-    fn get_part1(&self) -> fn(bool) -> () {
-        part1::run
-    }
+    type Parsed = Vec<Card>;
 
-    fn get_part2(&self) -> fn(bool) -> () {
-        part2::run
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        numbered_lines(input)
+            .map(|(n, line)| Card::from_str(line).map_err(|e| e.on_line(n)))
+            .collect()
     }
-}
 
-pub const DAY_4: Day4 = Day4();
+    fn part1(&self, parsed: &Self::Parsed) -> Answer {
+        part1::solve(parsed).into()
+    }
 
-fn filename(debug: bool) -> &'static str {
-    match debug {
-        true => "files/day4.example",
-        false => "files/day4.puzzle",
+    fn part2(&self, parsed: &Self::Parsed) -> Answer {
+        part2::solve(parsed).into()
     }
 }
 
+utils::register_day!(4, Day4());
+
 #[derive(Debug)]
-struct Card {
+pub struct Card {
     number: usize,
     winning: HashMap<u32, usize>,
     gotten: HashMap<u32, usize>,
@@ -70,67 +70,61 @@ impl Display for Card {
     }
 }
 
-fn into_hashmap(m: &Option<Match>, map: &mut HashMap<u32, usize>) {
-    let m = m.unwrap().as_str();
-    let nums = m
-        .trim()
-        .split(" ")
-        .filter_map(|num_part| u32::from_str(num_part.trim()).ok())
-        .collect::<Vec<_>>();
-
-    nums.into_iter().for_each(|num| {
+fn into_hashmap(line: &str, m: Match, map: &mut HashMap<u32, usize>) -> Result<(), ParseError> {
+    for num in scan::whitespace_ints::<u32>(m.as_str()).map_err(|e| e.in_line(line))? {
         let entry = map.entry(num).or_insert(0);
         *entry += 1;
-    });
+    }
+
+    Ok(())
 }
 
 impl FromStr for Card {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut regex = Regex::new(
+        if !s.contains('|') {
+            return Err(ParseError::in_line(
+                s,
+                s,
+                "`|` between the two sets of numbers",
+            ));
+        }
+
+        let regex = Regex::new(
             r"(?m)Card *(?P<number>\d*): (?P<winning>( *\d* *)*) | *(?P<gotten>(\d* *)*)",
         )
         .unwrap();
         let captures = regex.captures_iter(s);
 
-        let mut number: usize = 0;
+        let mut number = None;
         let mut winning = HashMap::<u32, usize>::new();
         let mut gotten = HashMap::<u32, usize>::new();
 
-        captures.for_each(|c| {
-            let winning_cap = c.name("winning");
-            let gotten_cap = c.name("gotten");
-            let number_cap = c.name("number");
-
-            if winning_cap.is_some() {
-                into_hashmap(&winning_cap, &mut winning);
+        for c in captures {
+            if let Some(winning_cap) = c.name("winning") {
+                into_hashmap(s, winning_cap, &mut winning)?;
             }
 
-            if gotten_cap.is_some() {
-                into_hashmap(&gotten_cap, &mut gotten);
+            if let Some(gotten_cap) = c.name("gotten") {
+                into_hashmap(s, gotten_cap, &mut gotten)?;
             }
 
-            if number_cap.is_some() {
-                number = usize::from_str(number_cap.unwrap().as_str().trim()).unwrap();
+            if let Some(number_cap) = c.name("number") {
+                let text = number_cap.as_str();
+                number = Some(scan::int(text).map_err(|e| e.in_line(s))?);
             }
-        });
+        }
 
+        let number = number.ok_or_else(|| ParseError::in_line(s, s, "`Card N:`"))?;
         Ok(Card::new(number, winning, gotten))
     }
 }
 
 mod part2 {
     use super::*;
-    use utils::lines_of_file;
-
-    fn solve(file: &str) -> u32 {
-        let lines = lines_of_file(file).unwrap();
-        let cards = lines
-            .iter()
-            .map(|line| Card::from_str(line).unwrap())
-            .collect::<Vec<_>>();
 
+    pub fn solve(cards: &[Card]) -> u32 {
         let mut number_of_each = HashMap::<usize, usize>::new();
 
         for card in cards.iter() {
@@ -148,35 +142,16 @@ mod part2 {
 
         number_of_each.values().sum::<usize>() as u32
     }
-
-    pub fn run(debug: bool) {
-        let filename = filename(debug);
-        let score = solve(filename);
-        println!("The score is {score}");
-    }
 }
 
 mod part1 {
     use crate::*;
 
-    fn solve(file: &str) -> u32 {
-        let lines = lines_of_file(file).unwrap();
-        let cards = lines
-            .iter()
-            .map(|line| Card::from_str(line).unwrap())
-            .collect::<Vec<_>>();
-
-        for card in &cards {
-            println!("{card}")
+    pub fn solve(cards: &[Card]) -> u32 {
+        for card in cards {
+            utils::debug!("{card}")
         }
 
         cards.iter().map(|c| c.score()).sum::<u32>()
     }
-
-    pub fn run(debug: bool) {
-        let filename = filename(debug);
-        let score = solve(filename);
-
-        println!("The score is {score}");
-    }
 }
@@ -1,28 +1,25 @@
-use std::collections::HashMap;
-use std::hash::Hash;
 use std::str::FromStr;
-use utils::Problem;
-
-fn filename(debug: bool) -> &'static str {
-    match debug {
-        false => "files/day5.puzzle",
-        true => "files/day5.example",
-    }
-}
+use utils::{scan, Answer, Interval, OffsetMap, ParseError, Problem, Section, Sections};
 
 pub struct Day5();
 
 impl Problem for Day5 {
-    fn get_part1(&self) -> fn(bool) -> () {
-        part1::solve
+    type Parsed = Almanac;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        Almanac::from_str(input)
     }
 
-    fn get_part2(&self) -> fn(bool) -> () {
-        part2::solve
+    fn part1(&self, parsed: &Self::Parsed) -> Answer {
+        part1::solve(parsed).into()
+    }
+
+    fn part2(&self, parsed: &Self::Parsed) -> Answer {
+        part2::solve(parsed).into()
     }
 }
 
-pub const DAY_5: Day5 = Day5();
+utils::register_day!(5, Day5());
 
 #[derive(Debug)]
 struct LargeRange {
@@ -32,17 +29,14 @@ struct LargeRange {
 }
 
 impl LargeRange {
-    pub fn get(&self, v: u32) -> Option<u32> {
-        if v >= self.src && v <= self.src + self.len - 1 {
-            let off = v - self.src;
-            Some(self.dest + off)
-        } else {
-            None
-        }
+    /// The values that this range moves.
+    fn source(&self) -> Interval<u64> {
+        Interval::from_len(self.src as u64, self.len as u64)
     }
 
-    pub fn to_hashmap(&self) -> HashMap<u32, u32> {
-        let mut ret = HashMap::new();
+    #[cfg(test)]
+    pub fn to_hashmap(&self) -> std::collections::HashMap<u32, u32> {
+        let mut ret = std::collections::HashMap::new();
 
         for i in 0..self.len {
             ret.insert(self.src + i, self.dest + i);
@@ -59,32 +53,32 @@ mod test_large_range {
     #[test]
     fn test_basic() {
         let r = LargeRange {
-            src: 98,
+            src: u32::MAX - 1,
             dest: 50,
             len: 2,
         };
 
-        assert_eq!(r.get(99), Some(51));
-        assert_eq!(r.get(98), Some(50));
-        assert!(r.get(100).is_none());
+        assert_eq!(u32::MAX as u64 - 1, r.source().start);
+        assert_eq!(u32::MAX as u64 + 1, r.source().end);
     }
 }
 
-fn parse_range(line: &str) -> LargeRange {
-    let range_desc = line
-        .split(" ")
-        .into_iter()
-        .filter(|line| line.trim().len() > 0)
-        .map(|num_part| u32::from_str(num_part).unwrap())
-        .collect::<Vec<_>>();
+fn parse_range(line: &str) -> Result<LargeRange, ParseError> {
+    let range_desc = scan::whitespace_ints::<u32>(line).map_err(|e| e.in_line(line))?;
 
-    assert_eq!(3, range_desc.len());
+    if range_desc.len() != 3 {
+        return Err(ParseError::in_line(
+            line,
+            line,
+            "a destination, a source and a length",
+        ));
+    }
 
     let dest = range_desc[0];
     let src = range_desc[1];
     let len = range_desc[2];
 
-    LargeRange { src, dest, len }
+    Ok(LargeRange { src, dest, len })
 }
 
 #[cfg(test)]
@@ -95,7 +89,7 @@ mod test_range_parsing {
     #[test]
     fn case1() {
         let line = "50 98 2";
-        let parsed = parse_range(line).to_hashmap();
+        let parsed = parse_range(line).unwrap().to_hashmap();
 
         let mut expected = HashMap::new();
         expected.insert(98_u32, 50_u32);
@@ -107,7 +101,7 @@ mod test_range_parsing {
     #[test]
     fn case2() {
         let line = "52 50 48";
-        let parsed = parse_range(line).to_hashmap();
+        let parsed = parse_range(line).unwrap().to_hashmap();
 
         assert_eq!(parsed.len(), 48);
         assert_eq!(parsed.get(&50).unwrap(), &52);
@@ -116,186 +110,171 @@ mod test_range_parsing {
 }
 
 #[derive(Debug, Default)]
-struct Almanac {
+pub struct Almanac {
     seeds: Vec<u32>,
-    seed_to_soil: Vec<LargeRange>,
-    soil_to_fertilizer: Vec<LargeRange>,
-    fertilizer_to_water: Vec<LargeRange>,
-    water_to_light: Vec<LargeRange>,
-    light_to_temperature: Vec<LargeRange>,
-    temperature_to_humidity: Vec<LargeRange>,
-    humidity_to_location: Vec<LargeRange>,
+    /// Every map, from seeds to locations, in the order of `MAPS`
+    maps: [OffsetMap<u64>; 7],
 }
 
-fn get(v: u32, ranges: &Vec<LargeRange>) -> Option<u32> {
-    let mut count = 0;
-    let mut found = None;
+impl Almanac {
+    fn destination_of_seed(&self, seed: u64) -> u64 {
+        self.maps.iter().fold(seed, |value, map| map.get(value))
+    }
+}
 
-    for r in ranges {
-        let candidate = r.get(v);
-        if candidate.is_some() {
-            count += 1;
-            found = candidate;
+/// Headers of the maps, from seeds to locations.
+const MAPS: [&str; 7] = [
+    "seed-to-soil map",
+    "soil-to-fertilizer map",
+    "fertilizer-to-water map",
+    "water-to-light map",
+    "light-to-temperature map",
+    "temperature-to-humidity map",
+    "humidity-to-location map",
+];
+
+fn read_map(section: &Section) -> Result<OffsetMap<u64>, ParseError> {
+    let mut map = OffsetMap::new();
+    for &(n, line) in &section.lines {
+        let range = parse_range(line).map_err(|e| e.on_line(n))?;
+        if let Err(other) = map.insert(range.source(), range.dest as u64) {
+            let expected = format!("a range that doesn't overlap {other}");
+            return Err(ParseError::in_line(line, line, expected).on_line(n));
         }
     }
-
-    assert!(count <= 1);
-
-    found
+    Ok(map)
 }
 
-impl Almanac {
-    fn destination_of_seed(&self, seed: u32) -> u32 {
-        let soil = get(seed, &self.seed_to_soil).unwrap_or(seed);
-        let fertilizer = get(soil, &self.soil_to_fertilizer).unwrap_or(soil);
-        let water = get(fertilizer, &self.fertilizer_to_water).unwrap_or(fertilizer);
-        let light = get(water, &self.water_to_light).unwrap_or(water);
-        let temp = get(light, &self.light_to_temperature).unwrap_or(light);
-        let hum = get(temp, &self.temperature_to_humidity).unwrap_or(temp);
-        get(hum, &self.humidity_to_location).unwrap_or(hum)
-    }
+impl FromStr for Almanac {
+    type Err = ParseError;
 
-    fn destination_of_seeds(&self) -> Vec<u32> {
-        let mut destinations = vec![];
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sections = Sections::named(s)?;
+
+        let section = sections.at(0, "seeds")?;
+        let (n, line) = section.first_line();
+        let seeds: Vec<u32> =
+            scan::whitespace_ints(section.rest).map_err(|e| e.in_line(line).on_line(n))?;
+        // Part 2 reads them as pairs of a start and a length
+        if seeds.is_empty() || seeds.len() % 2 == 1 {
+            let expected = match seeds.is_empty() {
+                true => "a seed",
+                false => "a length after the last seed",
+            };
+            return Err(ParseError::in_line(line, &line[line.len()..], expected).on_line(n));
+        }
+        if let Some(&(n, line)) = section.lines.first() {
+            let expected = "a blank line after the seeds";
+            return Err(ParseError::in_line(line, line, expected).on_line(n));
+        }
+
+        // Read in order, so that the first error in the input is reported
+        let mut maps: [OffsetMap<u64>; 7] = Default::default();
+        for (i, name) in MAPS.iter().enumerate() {
+            maps[i] = read_map(sections.at(i + 1, name)?)?;
+        }
 
-        for seed in self.seeds.iter() {
-            let seed = *seed;
-            let loc = self.destination_of_seed(seed);
-            destinations.push(loc);
+        let mut names = vec!["seeds"];
+        names.extend(MAPS);
+        sections.only(&names)?;
+        if let Some(extra) = sections.iter().nth(names.len()) {
+            return Err(extra.error("nothing after the maps"));
         }
 
-        destinations
+        Ok(Almanac { seeds, maps })
     }
 }
 
-fn skip_to_first_non_empty(lines: &Vec<&str>, i: &mut usize) {
-    while lines[*i].trim().len() == 0 {
-        *i += 1;
+#[cfg(test)]
+mod test_almanac_parsing {
+    use crate::Almanac;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_wrong_header() {
+        let s = "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\nsoil-to-water map:\n1 2 3\n";
+        let e = Almanac::from_str(s).unwrap_err();
+
+        assert_eq!(6, e.line);
+        assert_eq!("`soil-to-fertilizer map:`", e.expected);
     }
-}
 
-fn collect_non_empty(lines: &Vec<&str>, i: &mut usize) -> Vec<String> {
-    let mut ret = vec![];
-    while *i < lines.len() && lines[*i].trim().len() != 0 {
-        ret.push(lines[*i].to_string());
-        *i += 1;
+    #[test]
+    fn test_extra_section() {
+        let maps = crate::MAPS
+            .map(|name| format!("{name}:\n1 2 3\n\n"))
+            .concat();
+        let s = format!("seeds: 1 2\n\n{maps}seeds:\n");
+        let e = Almanac::from_str(&s).unwrap_err();
+        assert_eq!(
+            (24, "nothing after the maps"),
+            (e.line, e.expected.as_str())
+        );
+
+        let s = format!("seeds: 1 2\n\n{maps}plants map:\n");
+        let e = Almanac::from_str(&s).unwrap_err();
+        assert_eq!("plants map", e.text);
     }
 
-    ret
-}
+    #[test]
+    fn test_seeds() {
+        let e = Almanac::from_str("seeds:\n\nseed-to-soil map:\n1 2 3\n").unwrap_err();
+        assert_eq!((1, 7, "a seed"), (e.line, e.column, e.expected.as_str()));
 
-fn push_all_into(target: &mut Vec<LargeRange>, src: Vec<LargeRange>) {
-    for range in src {
-        target.push(range)
+        let e = Almanac::from_str("seeds: 79 14 55\n\nseed-to-soil map:\n").unwrap_err();
+        assert_eq!((1, 16), (e.line, e.column));
+        assert_eq!("a length after the last seed", e.expected);
     }
-}
 
-impl FromStr for Almanac {
-    type Err = ();
+    #[test]
+    fn test_bad_range() {
+        let s = "seeds: 1 2\n\nseed-to-soil map:\n1 2\n";
+        let e = Almanac::from_str(s).unwrap_err();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut almanac = Almanac::default();
-        let lines: Vec<&str> = s.lines().collect();
-
-        let mut i = 0;
-        // Read seeds
-        let line = &lines[i];
-        let seeds_in_str: String = line
-            .chars()
-            .into_iter()
-            .filter(|c| c.is_numeric() || *c == ' ')
-            .collect();
-        seeds_in_str
-            .split(" ")
-            .filter(|s| s.trim().len() > 0)
-            .for_each(|seed_as_str| {
-                almanac.seeds.push(u32::from_str(seed_as_str).unwrap());
-            });
-        i += 1;
-
-        skip_to_first_non_empty(&lines, &mut i);
-        assert_eq!(lines[i], "seed-to-soil map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.seed_to_soil, ranges);
-        i += 1;
-
-        assert_eq!(lines[i], "soil-to-fertilizer map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.soil_to_fertilizer, ranges);
-        i += 1;
-
-        assert_eq!(lines[i], "fertilizer-to-water map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.fertilizer_to_water, ranges);
-        i += 1;
-
-        assert_eq!(lines[i], "water-to-light map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.water_to_light, ranges);
-        i += 1;
-
-        assert_eq!(lines[i], "light-to-temperature map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.light_to_temperature, ranges);
-        i += 1;
-
-        assert_eq!(lines[i], "temperature-to-humidity map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.temperature_to_humidity, ranges);
-        i += 1;
-
-        assert_eq!(lines[i], "humidity-to-location map:");
-        i += 1;
-        let ranges = collect_non_empty(&lines, &mut i)
-            .into_iter()
-            .map(|sl| parse_range(&sl))
-            .collect::<Vec<_>>();
-        push_all_into(&mut almanac.humidity_to_location, ranges);
-
-        Ok(almanac)
+        assert_eq!((4, 1), (e.line, e.column));
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        let s = "seeds: 1 2\n\nseed-to-soil map:\n52 50 48\n50 60 2\n";
+        let e = Almanac::from_str(s).unwrap_err();
+
+        assert_eq!((5, 1, "50 60 2"), (e.line, e.column, e.text.as_str()));
+        assert_eq!("a range that doesn't overlap [50, 98)", e.expected);
     }
 }
 
 mod part2 {
-    use crate::{filename, Almanac};
-    use std::str::FromStr;
-    use utils::str_of_file;
+    use crate::{Almanac, MAPS};
+    use utils::{span, Interval, IntervalSet};
+
+    /// Move the seed ranges through the maps as whole intervals, splitting
+    /// them where the map ranges start and end.
+    pub fn solve(almanac: &Almanac) -> u64 {
+        let mut values: IntervalSet<u64> = almanac
+            .seeds
+            .chunks_exact(2)
+            .map(|pair| Interval::from_len(pair[0] as u64, pair[1] as u64))
+            .collect();
+
+        for (name, map) in MAPS.iter().zip(&almanac.maps) {
+            let _s = span(*name);
+            values = map.map_set(&values);
+        }
 
-    fn run(filename: &str) -> u32 {
-        let almanac_as_str = str_of_file(filename).expect("Failed to open the file.");
-        let almanac = Almanac::from_str(&almanac_as_str).unwrap();
+        values.min().expect("No seeds")
+    }
 
-        let mut lowest = u32::MAX;
+    /// Every seed one after the other, as slow as it gets.
+    #[cfg(test)]
+    pub fn brute_force(almanac: &Almanac) -> u64 {
+        let mut lowest = u64::MAX;
         let n = almanac.seeds.len();
 
         // Make the CPU cry
         for pair_idx in 0..(n / 2) {
-            let start = almanac.seeds[pair_idx * 2];
-            let len = almanac.seeds[1 + pair_idx * 2];
+            let start = almanac.seeds[pair_idx * 2] as u64;
+            let len = almanac.seeds[1 + pair_idx * 2] as u64;
 
             for seed in start..(start + len) {
                 let loc = almanac.destination_of_seed(seed);
@@ -307,26 +286,104 @@ mod part2 {
 
         lowest
     }
-
-    pub fn solve(debug: bool) {
-        let result = run(filename(debug));
-        println!("The lowest location number is {result}");
-    }
 }
 
-mod part1 {
-    use crate::{filename, Almanac};
+#[cfg(test)]
+mod part2_tests {
+    use crate::Almanac;
     use std::str::FromStr;
-    use utils::str_of_file;
+    use utils::{Differential, Interval, OffsetMap, Rng};
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
 
-    fn run(filename: &str) -> u32 {
-        let almanac_as_str = str_of_file(filename).expect("Failed to open the file.");
-        let almanac = Almanac::from_str(&almanac_as_str).unwrap();
-        *almanac.destination_of_seeds().iter().min().unwrap()
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn test_ranges_like_brute_force() {
+        let almanac = Almanac::from_str(EXAMPLE).unwrap();
+        assert_eq!(46, crate::part2::solve(&almanac));
+        assert_eq!(46, crate::part2::brute_force(&almanac));
     }
 
-    pub fn solve(debug: bool) {
-        let result = run(filename(debug));
-        println!("The lowest location number is {result}");
+    /// A map of a few ranges that don't overlap, somewhere in `0..200`.
+    fn random_map(rng: &mut Rng) -> OffsetMap<u64> {
+        let mut map = OffsetMap::new();
+        let mut src = rng.range(0..20);
+        for _ in 0..rng.range(0..6) {
+            // Empty ranges included, which move nothing
+            let len = rng.range(0..40);
+            let dest = rng.range(0..200);
+            map.insert(Interval::from_len(src, len), dest).unwrap();
+            src += len + rng.range(0..20);
+        }
+        map
+    }
+
+    fn random_almanac(rng: &mut Rng) -> Almanac {
+        let mut seeds = vec![];
+        for _ in 0..rng.range(1..5) {
+            seeds.push(rng.range(0..200) as u32);
+            seeds.push(rng.range(1..30) as u32);
+        }
+
+        Almanac {
+            seeds,
+            maps: std::array::from_fn(|_| random_map(rng)),
+        }
+    }
+
+    #[test]
+    fn test_ranges_like_brute_force_random() {
+        Differential::new("day5 part2", random_almanac)
+            .reference(crate::part2::brute_force)
+            .fast(crate::part2::solve)
+            .cases(2000)
+            .run();
+    }
+}
+
+mod part1 {
+    use crate::Almanac;
+    use utils::span;
+
+    pub fn solve(almanac: &Almanac) -> u64 {
+        let _s = span("destination_of_seeds");
+        almanac
+            .seeds
+            .iter()
+            .map(|&seed| almanac.destination_of_seed(seed as u64))
+            .min()
+            .expect("No seeds")
     }
 }
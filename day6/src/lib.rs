@@ -1,39 +1,52 @@
-use std::arch::x86_64::_bextr2_u64;
 use std::str::FromStr;
-use utils::{filename, str_of_file, Problem};
+use utils::{scan, Answer, LineReader, ParseError, Problem, Trim};
 
 pub struct Day6();
 
 impl Problem for Day6 {
-    fn get_part1(&self) -> fn(bool) -> () {
-        part1::run
+    type Parsed = Races;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        Races::from_str(input)
+    }
+
+    fn part1(&self, parsed: &Self::Parsed) -> Answer {
+        part1::solve(parsed).into()
     }
 
-    fn get_part2(&self) -> fn(bool) -> () {
-        part2::run
+    fn part2(&self, parsed: &Self::Parsed) -> Answer {
+        part2::solve(parsed).into()
     }
 }
 
-pub const DAY_6: Day6 = Day6();
+utils::register_day!(6, Day6());
 
+#[derive(Debug)]
 pub struct Races {
     times: Vec<u64>,
     dists: Vec<u64>,
 }
 
-impl Races {
-    pub fn into_super_race(self) -> Self {
-        let time = self.times.iter().map(|x| x.to_string()).reduce(|a, b| format!("{a}{b}")).unwrap();
-        let dist = self.dists.iter().map(|x| x.to_string()).reduce(|a, b| format!("{a}{b}")).unwrap();
+/// The numbers written one after the other as a single one, if it fits.
+fn concatenated(numbers: &[u64]) -> Option<u64> {
+    let digits: String = numbers.iter().map(u64::to_string).collect();
+    scan::int(&digits).ok()
+}
 
-        let i =5;
+impl Races {
+    /// The races as one, their numbers being put together. Parsing checks
+    /// that they fit.
+    pub fn to_super_race(&self) -> Self {
+        let fits = "The race was checked to fit when parsing";
         Races {
-            times : vec![u64::from_str(&time).unwrap()],
-            dists : vec![u64::from_str(&dist).unwrap()]
+            times: vec![concatenated(&self.times).expect(fits)],
+            dists: vec![concatenated(&self.dists).expect(fits)],
         }
     }
 }
 
+/// Ways to beat the record, holding the button every possible time.
+#[cfg(test)]
 fn num_beats(time: u64, dist: u64) -> u64 {
     let mut can_beat = 0;
     for held in 1..time {
@@ -47,70 +60,189 @@ fn num_beats(time: u64, dist: u64) -> u64 {
     can_beat
 }
 
+/// Ways to beat the record, solved for: holding the button `held` ms beats it
+/// when `held * (time - held) > dist`, which holds between the two roots of
+/// the quadratic, symmetrically around `time / 2`.
+fn num_beats_closed_form(time: u64, dist: u64) -> u64 {
+    let (time, dist) = (time as u128, dist as u128);
+    let beats = |held: u128| held * (time - held) > dist;
+
+    let Some(discriminant) = (time * time).checked_sub(4 * dist) else {
+        return 0;
+    };
+
+    // Rounding of the square root aside, `lowest` is the smaller root
+    let mut lowest = (time - discriminant.isqrt()) / 2;
+    while lowest > 0 && beats(lowest - 1) {
+        lowest -= 1;
+    }
+    while lowest <= time / 2 && !beats(lowest) {
+        lowest += 1;
+    }
+
+    match lowest > time / 2 {
+        true => 0,
+        false => (time - 2 * lowest + 1) as u64,
+    }
+}
+
+/// Numbers of a line, after its `header`. There must be at least one, as
+/// every part works on the product of the races.
+fn parse_numbers(line: &str, header: &str) -> Result<Vec<u64>, ParseError> {
+    let numbers = line
+        .strip_prefix(header)
+        .ok_or_else(|| ParseError::in_line(line, line, format!("`{header}`")))?;
+
+    let numbers = scan::whitespace_ints(numbers).map_err(|e| e.in_line(line))?;
+    if numbers.is_empty() {
+        return Err(ParseError::in_line(line, &line[line.len()..], "a number"));
+    }
+    Ok(numbers)
+}
+
 impl FromStr for Races {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<_>>();
-        assert_eq!(2, lines.len());
-
-        let times = lines[0]
-            .split_whitespace()
-            .skip(1)
-            .map(|num| u64::from_str(num).unwrap())
-            .collect::<Vec<_>>();
-        let dists = lines[1]
-            .split_whitespace()
-            .skip(1)
-            .map(|num| u64::from_str(num).unwrap())
-            .collect::<Vec<_>>();
+        let lines: Vec<_> = LineReader::new()
+            .trim(Trim::End)
+            .keep_empty(false)
+            .numbered(s)
+            .collect();
+        let [(n_times, times_line), (n_dists, dists_line), ref rest @ ..] = lines[..] else {
+            return Err(ParseError::at_end(
+                s,
+                "a line of times and a line of distances",
+            ));
+        };
+        if let Some(&(n, extra)) = rest.first() {
+            return Err(
+                ParseError::in_line(extra, extra, "nothing after the distances").on_line(n),
+            );
+        }
+
+        let times = parse_numbers(times_line, "Time:").map_err(|e| e.on_line(n_times))?;
+        let dists = parse_numbers(dists_line, "Distance:").map_err(|e| e.on_line(n_dists))?;
+
+        for (numbers, line, n) in [(&times, times_line, n_times), (&dists, dists_line, n_dists)] {
+            if concatenated(numbers).is_none() {
+                let (_, text) = line.split_once(':').unwrap();
+                let expected = "numbers that fit in u64 once put together";
+                return Err(ParseError::in_line(line, text.trim(), expected).on_line(n));
+            }
+        }
+
+        if times.len() != dists.len() {
+            let expected = format!("as many distances as times ({0})", times.len());
+            return Err(ParseError::in_line(dists_line, dists_line, expected).on_line(n_dists));
+        }
 
         Ok(Races { times, dists })
     }
 }
 
+#[cfg(test)]
+mod test_races_parsing {
+    use crate::Races;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse() {
+        let races = Races::from_str("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+        assert_eq!(vec![7, 15, 30], races.times);
+        assert_eq!(vec![9, 40, 200], races.dists);
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let races = Races::from_str("\nTime: 7 15\r\n\nDistance: 9 40\r\n\n").unwrap();
+        assert_eq!(vec![9, 40], races.dists);
+
+        let e = Races::from_str("Time: 7\nDistance: 9\n\nTime: 8\n").unwrap_err();
+        assert_eq!(4, e.line);
+    }
+
+    #[test]
+    fn test_no_races() {
+        let e = Races::from_str("Time:\nDistance:\n").unwrap_err();
+        assert_eq!((1, 6), (e.line, e.column));
+        assert_eq!("a number", e.expected);
+
+        let e = Races::from_str("Time: 7\nDistance:   \n").unwrap_err();
+        assert_eq!((2, 10), (e.line, e.column));
+    }
+
+    #[test]
+    fn test_super_race_too_long() {
+        let e = Races::from_str("Time: 99999999999 99999999999\nDistance: 9 40\n").unwrap_err();
+        assert_eq!((1, 7), (e.line, e.column));
+        assert_eq!("99999999999 99999999999", e.text);
+
+        let e = Races::from_str("Time: 7 15\nDistance: 9999999999 9999999999\n").unwrap_err();
+        assert_eq!(2, e.line);
+    }
+
+    #[test]
+    fn test_mismatched_lengths() {
+        let e = Races::from_str("Time: 7 15\nDistance: 9").unwrap_err();
+        assert_eq!(2, e.line);
+    }
+}
+
+#[cfg(test)]
+mod test_num_beats {
+    use crate::{num_beats, num_beats_closed_form};
+    use utils::Differential;
+
+    #[test]
+    fn test_example() {
+        assert_eq!(4, num_beats_closed_form(7, 9));
+        assert_eq!(8, num_beats_closed_form(15, 40));
+        assert_eq!(9, num_beats_closed_form(30, 200));
+        assert_eq!(71503, num_beats_closed_form(71530, 940200));
+    }
+
+    #[test]
+    fn test_closed_form_like_loop() {
+        Differential::new("num_beats", |rng| {
+            let time = rng.range(0..1000);
+            // Around the best distance, which is reached holding for time / 2
+            let best = (time / 2) * (time - time / 2);
+            (time, rng.range(0..best + 10))
+        })
+        .reference(|&(time, dist)| num_beats(time, dist))
+        .fast(|&(time, dist)| num_beats_closed_form(time, dist))
+        .cases(5000)
+        .run();
+    }
+}
+
 mod part1 {
     use super::*;
 
-    fn solve(s: &str) -> u64 {
-        let races = Races::from_str(s).unwrap();
-
+    pub fn solve(races: &Races) -> u64 {
         races
             .times
             .iter()
-            .zip(races.dists)
-            .map(|(time, dist)| num_beats(*time, dist))
-            .reduce(|a, b| a * b).unwrap()
-    }
-
-    pub fn run(debug: bool) {
-        let filename = filename(6, debug);
-        println!(
-            "Result of multiplication: {0}",
-            solve(&str_of_file(&filename).unwrap())
-        );
+            .zip(&races.dists)
+            .map(|(time, dist)| num_beats_closed_form(*time, *dist))
+            .reduce(|a, b| a * b)
+            .unwrap()
     }
 }
 
 mod part2 {
     use super::*;
-    fn solve(s: &str) -> u64 {
-        let races = Races::from_str(s).unwrap();
-        let races = races.into_super_race();
+
+    pub fn solve(races: &Races) -> u64 {
+        let races = races.to_super_race();
 
         races
             .times
             .iter()
             .zip(races.dists)
-            .map(|(time, dist)| num_beats(*time, dist))
-            .reduce(|a, b| a * b).unwrap()
-    }
-
-    pub fn run(debug: bool) {
-        let filename = filename(6, debug);
-        println!(
-            "Result of super-race: {0}",
-            solve(&str_of_file(&filename).unwrap())
-        );
+            .map(|(time, dist)| num_beats_closed_form(*time, dist))
+            .reduce(|a, b| a * b)
+            .unwrap()
     }
 }
@@ -0,0 +1,167 @@
+use std::fmt::{Display, Formatter};
+
+/// Heap usage of a run, on the thread it ran on.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AllocStats {
+    /// Number of allocations and reallocations
+    pub count: u64,
+    /// Bytes requested over the whole run
+    pub bytes: u64,
+    /// Most bytes alive at once
+    pub peak: u64,
+}
+
+/// Whether the tracking allocator is compiled in, with the `alloc-stats` feature.
+pub const TRACKING: bool = cfg!(feature = "alloc-stats");
+
+#[cfg(feature = "alloc-stats")]
+mod tracking {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    // Plain const cells, which need no destructor, so that they are safe to
+    // use from inside the allocator
+    thread_local! {
+        pub static COUNT: Cell<u64> = const { Cell::new(0) };
+        pub static BYTES: Cell<u64> = const { Cell::new(0) };
+        pub static CURRENT: Cell<i64> = const { Cell::new(0) };
+        pub static PEAK: Cell<i64> = const { Cell::new(0) };
+    }
+
+    fn record(grown: i64) {
+        COUNT.with(|count| count.set(count.get() + 1));
+        BYTES.with(|bytes| bytes.set(bytes.get() + grown.max(0) as u64));
+        shift(grown);
+    }
+
+    fn shift(by: i64) {
+        let current = CURRENT.with(|current| {
+            current.set(current.get() + by);
+            current.get()
+        });
+        PEAK.with(|peak| peak.set(peak.get().max(current)));
+    }
+
+    struct Tracking;
+
+    unsafe impl GlobalAlloc for Tracking {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record(layout.size() as i64);
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record(layout.size() as i64);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            shift(-(layout.size() as i64));
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                record(new_size as i64 - layout.size() as i64);
+            }
+            new
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: Tracking = Tracking;
+}
+
+/// Run `f`, along with how much it allocated on this thread when tracking.
+#[cfg(feature = "alloc-stats")]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    use tracking::{BYTES, COUNT, CURRENT, PEAK};
+
+    for cell in [&COUNT, &BYTES] {
+        cell.with(|c| c.set(0));
+    }
+    for cell in [&CURRENT, &PEAK] {
+        cell.with(|c| c.set(0));
+    }
+
+    let result = f();
+
+    let stats = AllocStats {
+        count: COUNT.with(|c| c.get()),
+        bytes: BYTES.with(|c| c.get()),
+        peak: PEAK.with(|c| c.get()).max(0) as u64,
+    };
+    (result, Some(stats))
+}
+
+/// Run `f`, along with how much it allocated on this thread when tracking.
+#[cfg(not(feature = "alloc-stats"))]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    (f(), None)
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{value:.1} {0}", UNITS[unit]),
+    }
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{0} allocs, {1} total, {2} peak",
+            self.count,
+            human_bytes(self.bytes),
+            human_bytes(self.peak)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alloc::{human_bytes, measure, AllocStats, TRACKING};
+
+    #[test]
+    fn test_human_bytes() {
+        assert_eq!("512 B", human_bytes(512));
+        assert_eq!("1.5 KiB", human_bytes(1536));
+        assert_eq!("3.0 MiB", human_bytes(3 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_measure() {
+        let (sum, stats) = measure(|| {
+            let big: Vec<u64> = (0..1000).collect();
+            let small = "0123456789".to_string();
+            big.iter().sum::<u64>() + small.len() as u64
+        });
+        assert_eq!(499510, sum);
+
+        if TRACKING {
+            let stats = stats.unwrap();
+            assert_eq!(2, stats.count);
+            assert_eq!(8010, stats.bytes);
+            assert_eq!(8010, stats.peak);
+        } else {
+            assert_eq!(None::<AllocStats>, stats);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+use utils::{Day, InputSource, ParseError};
+
+pub struct BenchOptions {
+    pub iterations: usize,
+    pub warmup: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            iterations: 100,
+            warmup: 3,
+        }
+    }
+}
+
+/// Summary of a series of timings.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn of(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+
+        // Nearest rank
+        let rank = |p: f64| sorted[((p * n as f64).ceil() as usize).clamp(1, n) - 1];
+        let total: Duration = sorted.iter().sum();
+
+        Some(Stats {
+            min: sorted[0],
+            median: rank(0.5),
+            mean: total / n as u32,
+            p95: rank(0.95),
+        })
+    }
+}
+
+/// Run one part of a day over and over and print how long parsing and
+/// solving took.
+pub fn bench(
+    day: &Day,
+    part: u8,
+    source: &InputSource,
+    input: &str,
+    options: &BenchOptions,
+) -> Result<(), ParseError> {
+    for _ in 0..options.warmup {
+        day.run(part, input)?;
+    }
+
+    let mut parse_times = vec![];
+    let mut solve_times = vec![];
+    for _ in 0..options.iterations {
+        let run = day.run(part, input)?;
+        parse_times.push(run.parse_time);
+        solve_times.push(run.solve_time);
+    }
+
+    println!(
+        "Day {0} part {part} ({source}), {1} iterations after {2} warm-up",
+        day.number, options.iterations, options.warmup
+    );
+    println!(
+        "{0:<8}{1:>12}{2:>12}{3:>12}{4:>12}",
+        "", "min", "median", "mean", "p95"
+    );
+    for (name, samples) in [("parse", &parse_times), ("solve", &solve_times)] {
+        let stats = Stats::of(samples).expect("At least one iteration is needed");
+        println!(
+            "{name:<8}{0:>12}{1:>12}{2:>12}{3:>12}",
+            format!("{:.2?}", stats.min),
+            format!("{:.2?}", stats.median),
+            format!("{:.2?}", stats.mean),
+            format!("{:.2?}", stats.p95),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<_> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::of(&samples).unwrap();
+
+        assert_eq!(Duration::from_millis(1), stats.min);
+        assert_eq!(Duration::from_millis(10), stats.median);
+        assert_eq!(Duration::from_micros(10500), stats.mean);
+        assert_eq!(Duration::from_millis(19), stats.p95);
+    }
+
+    #[test]
+    fn test_stats_of_nothing() {
+        assert_eq!(None, Stats::of(&[]));
+    }
+}
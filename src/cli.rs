@@ -0,0 +1,378 @@
+use crate::bench::BenchOptions;
+use crate::client::Client;
+use crate::registry::DAYS;
+use crate::report::Format;
+use crate::submit::DEFAULT_HISTORY_FILE;
+use crate::ProblemType;
+use std::path::PathBuf;
+use std::str::FromStr;
+use utils::{base_kind, find_day, Day, InputLayout, InputSource, DEFAULT_ANSWERS_FILE};
+
+pub const USAGE: &str = "Usage: ./aoc2023 DAY PART TYPE [--input PATH|-] [--watch]
+       ./aoc2023 DAY PART --input PATH|-
+       ./aoc2023 all
+       ./aoc2023 list
+       ./aoc2023 verify [DAY]
+       ./aoc2023 bench DAY [PART] [TYPE]
+       ./aoc2023 fetch DAY
+       ./aoc2023 submit DAY PART
+       ./aoc2023 new DAY
+       ./aoc2023 extract DAY PAGE [--pick N,...]
+
+Options:
+    --input PATH     Read the input from PATH instead of the default layout, - for stdin
+    --input-dir DIR  Directory holding the dayN.example/dayN.puzzle inputs (default: files)
+    --answers PATH   Expected answers to verify against (default: answers.toml)
+    --iterations N   Number of timed runs when benchmarking (default: 100)
+    --warmup N       Number of untimed runs before benchmarking (default: 3)
+    -v, -vv, -vvv    Print more of what the solutions log: info, debug, trace
+    -q               Only print errors from the solutions
+    --sequential     Run one thing at a time, for more accurate timings
+    --trace PATH     Write the timing spans of the run to PATH, in Chrome trace format
+    --watch          Run again whenever the inputs or the answers of the day change
+    --pick N,...     Blocks of the page to save as examples, instead of asking
+    --format FORMAT  Print the results of runs as text, json or csv (default: text)
+    --history PATH   Record of submitted answers (default: submissions.toml)
+    --base-url URL   Advent of Code server to talk to (default: https://adventofcode.com)";
+
+pub enum Command {
+    Run {
+        day: &'static Day,
+        part: u8,
+        source: InputSource,
+        /// Kind of input for reports, `custom` when given with --input
+        kind: String,
+        watch: bool,
+    },
+    /// Run a part on each of its numbered inputs, checking their answers
+    Examples {
+        day: &'static Day,
+        part: u8,
+        kinds: Vec<String>,
+    },
+    All,
+    List,
+    Verify {
+        day: Option<&'static Day>,
+    },
+    Bench {
+        day: &'static Day,
+        parts: Vec<(u8, InputSource)>,
+        options: BenchOptions,
+    },
+    Fetch {
+        day: u32,
+    },
+    Submit {
+        day: &'static Day,
+        part: u8,
+        source: InputSource,
+    },
+    New {
+        day: u32,
+    },
+    Extract {
+        day: u32,
+        page: PathBuf,
+        pick: Option<Vec<usize>>,
+    },
+}
+
+pub struct Args {
+    pub command: Command,
+    pub layout: InputLayout,
+    pub answers: PathBuf,
+    pub history: PathBuf,
+    pub format: Format,
+    pub base_url: String,
+    pub sequential: bool,
+    pub trace: Option<PathBuf>,
+    /// Steps above the default log level, or below if negative
+    pub verbosity: i32,
+}
+
+fn parse_day(s: &str) -> Result<&'static Day, String> {
+    find_day(DAYS, s).ok_or_else(|| format!("Not a valid day, got {s}"))
+}
+
+/// Number of a day that may not exist yet.
+fn parse_day_number(s: &str) -> Result<u32, String> {
+    let number = s.strip_prefix("day").unwrap_or(s);
+    match u32::from_str(number) {
+        Ok(day @ 1..) => Ok(day),
+        _ => Err(format!("Not a valid day number, got {s}")),
+    }
+}
+
+/// Kind of input, a problem type possibly numbered, like `example2`.
+fn parse_kind(s: &str) -> Result<String, String> {
+    match ProblemType::from_str(base_kind(s)) {
+        Some(_) => Ok(s.to_ascii_lowercase()),
+        None => Err(format!("Not a valid problem type, got {s}")),
+    }
+}
+
+fn parse_count(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a number"))?;
+    usize::from_str(&value).map_err(|_| format!("{flag} requires a number, got {value}"))
+}
+
+fn parse_part(s: &str) -> Result<u8, String> {
+    match u8::from_str(s) {
+        Ok(part @ (1 | 2)) => Ok(part),
+        _ => Err(format!("Part must be 1 or 2, got {s}")),
+    }
+}
+
+/// Parse the command line arguments, without the program name.
+pub fn parse(args: Vec<String>) -> Result<Args, String> {
+    let mut positional = vec![];
+    let mut input = None;
+    let mut layout = InputLayout::from_env();
+    let mut answers = PathBuf::from(DEFAULT_ANSWERS_FILE);
+    let mut history = PathBuf::from(DEFAULT_HISTORY_FILE);
+    let mut bench_options = BenchOptions::default();
+    let mut format = Format::Text;
+    let mut watch = false;
+    let mut sequential = false;
+    let mut trace = None;
+    let mut verbosity = 0;
+    let mut pick = None;
+    let mut base_url = Client::base_url_from_env();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                let value = args.next().ok_or("--input requires a path")?;
+                input = Some(InputSource::from_arg(&value));
+            }
+            "--input-dir" => {
+                let value = args.next().ok_or("--input-dir requires a directory")?;
+                layout = InputLayout::new(value);
+            }
+            "--answers" => {
+                answers = args.next().ok_or("--answers requires a path")?.into();
+            }
+            "--history" => {
+                history = args.next().ok_or("--history requires a path")?.into();
+            }
+            "--iterations" => match parse_count(&arg, args.next())? {
+                0 => return Err("--iterations must be at least 1".to_string()),
+                n => bench_options.iterations = n,
+            },
+            "--warmup" => bench_options.warmup = parse_count(&arg, args.next())?,
+            "--watch" => watch = true,
+            "--sequential" => sequential = true,
+            "-q" | "--quiet" => verbosity -= 1,
+            "--verbose" => verbosity += 1,
+            v if v.len() > 1 && v[1..].bytes().all(|b| b == b'v') && v.starts_with('-') => {
+                verbosity += v.len() as i32 - 1
+            }
+            "--trace" => trace = Some(args.next().ok_or("--trace requires a path")?.into()),
+            "--pick" => {
+                let value = args.next().ok_or("--pick requires block numbers")?;
+                let numbers = value
+                    .split(',')
+                    .map(|n| usize::from_str(n.trim()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("--pick requires block numbers, got {value}"))?;
+                pick = Some(numbers);
+            }
+            "--format" => {
+                let value = args.next().ok_or("--format requires text, json or csv")?;
+                format = Format::from_str(&value)
+                    .ok_or_else(|| format!("Not a valid format, got {value}"))?;
+            }
+            "--base-url" => base_url = args.next().ok_or("--base-url requires a URL")?,
+            "-" => positional.push(arg),
+            flag if flag.starts_with('-') => return Err(format!("Unknown option {flag}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = match positional.as_slice() {
+        [all] if all == "all" => {
+            if input.is_some() {
+                return Err("--input cannot be used with all".to_string());
+            }
+            Command::All
+        }
+        [list] if list == "list" => Command::List,
+        [verify, rest @ ..] if verify == "verify" && rest.len() <= 1 => {
+            let day = match rest.first() {
+                Some(day) => Some(parse_day(day)?),
+                None => None,
+            };
+            Command::Verify { day }
+        }
+        [fetch, day] if fetch == "fetch" => Command::Fetch {
+            day: parse_day_number(day)?,
+        },
+        [new, day] if new == "new" => Command::New {
+            day: parse_day_number(day)?,
+        },
+        [extract, day, page] if extract == "extract" => Command::Extract {
+            day: parse_day_number(day)?,
+            page: page.into(),
+            pick: pick.take(),
+        },
+        [submit, day, part] if submit == "submit" => {
+            let day = parse_day(day)?;
+            let part = parse_part(part)?;
+            let source = input.unwrap_or_else(|| {
+                InputSource::File(layout.path(day.number, part, ProblemType::Puzzle.name()))
+            });
+            Command::Submit { day, part, source }
+        }
+        [bench, day, rest @ ..] if bench == "bench" && rest.len() <= 2 => {
+            let day = parse_day(day)?;
+
+            let puzzle = || ProblemType::Puzzle.name().to_string();
+            let (parts, kind) = match rest {
+                [] => (vec![1, 2], puzzle()),
+                [part] if u8::from_str(part).is_ok() => (vec![parse_part(part)?], puzzle()),
+                [kind] => (vec![1, 2], parse_kind(kind)?),
+                [part, kind] => (vec![parse_part(part)?], parse_kind(kind)?),
+                _ => unreachable!(),
+            };
+
+            let parts = parts
+                .into_iter()
+                .map(|part| {
+                    let source = match &input {
+                        Some(source) => source.clone(),
+                        None => InputSource::File(layout.path(day.number, part, &kind)),
+                    };
+                    (part, source)
+                })
+                .collect();
+
+            Command::Bench {
+                day,
+                parts,
+                options: bench_options,
+            }
+        }
+        [day, part, rest @ ..] if rest.len() <= 1 => {
+            let day = parse_day(day)?;
+            let part = parse_part(part)?;
+
+            let kind = match rest.first() {
+                Some(kind) => Some(parse_kind(kind)?),
+                None => None,
+            };
+
+            let (source, kind) = match (input, kind) {
+                (Some(source), _) => (source, "custom".to_string()),
+                (None, Some(kind)) => (
+                    InputSource::File(layout.path(day.number, part, &kind)),
+                    kind,
+                ),
+                (None, None) => return Err("Either TYPE or --input is required".to_string()),
+            };
+
+            // A plain TYPE runs all the numbered inputs of that type, if there are some
+            let kinds = match ProblemType::from_str(&kind) {
+                Some(_) => layout.kinds(day.number, part, &kind),
+                None => vec![],
+            };
+
+            if watch && source == InputSource::Stdin {
+                return Err("--watch needs an input file, not stdin".to_string());
+            } else if watch && kinds.len() > 1 {
+                return Err(format!(
+                    "--watch needs a single input, one of {0}",
+                    kinds.join(", ")
+                ));
+            }
+
+            if kinds.len() > 1 {
+                Command::Examples { day, part, kinds }
+            } else {
+                Command::Run {
+                    day,
+                    part,
+                    source,
+                    kind,
+                    watch,
+                }
+            }
+        }
+        _ => return Err("Invalid arguments".to_string()),
+    };
+
+    if watch && !matches!(command, Command::Run { .. }) {
+        return Err("--watch only works when running a single part".to_string());
+    }
+    if pick.is_some() {
+        return Err("--pick only works with extract".to_string());
+    }
+    if watch && format != Format::Text {
+        return Err("--watch only prints text".to_string());
+    }
+    let has_report = matches!(
+        command,
+        Command::Run { .. } | Command::Examples { .. } | Command::All
+    );
+    if format != Format::Text && !has_report {
+        return Err("--format only works when running parts or all".to_string());
+    }
+
+    Ok(Args {
+        command,
+        layout,
+        answers,
+        history,
+        format,
+        base_url,
+        sequential,
+        trace,
+        verbosity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{parse, Command};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_fetch_day_without_crate() {
+        // Inputs are fetched before the crate of the day is created
+        let parsed = parse(args("fetch day25")).unwrap();
+        assert!(matches!(parsed.command, Command::Fetch { day: 25 }));
+        assert!(matches!(
+            parse(args("fetch 7")).unwrap().command,
+            Command::Fetch { day: 7 }
+        ));
+
+        assert!(parse(args("fetch day0")).is_err());
+        assert!(parse(args("fetch tomorrow")).is_err());
+    }
+
+    #[test]
+    fn test_format_only_with_reports() {
+        assert!(parse(args("all --format json")).is_ok());
+        assert!(parse(args("day1 1 example --format csv")).is_ok());
+
+        for command in [
+            "verify",
+            "list",
+            "bench day1",
+            "fetch day7",
+            "submit day1 1",
+        ] {
+            let e = parse(args(&format!("{command} --format json"))).err();
+            assert_eq!(
+                Some("--format only works when running parts or all"),
+                e.as_deref(),
+                "{command}"
+            );
+        }
+        assert!(parse(args("verify --format text")).is_ok());
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Environment variable overriding [`DEFAULT_BASE_URL`]
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+/// Environment variable holding the session token
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// File the session token is read from when [`SESSION_VAR`] is not set
+pub const SESSION_FILE: &str = ".aoc-session";
+
+const YEAR: u32 = 2023;
+/// Automated tools should say who runs them, so this points to the repository
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+#[derive(Debug)]
+pub enum ClientError {
+    NoSession,
+    Status(u16, String),
+    Transport(String),
+    Io(std::io::Error),
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::NoSession => write!(
+                f,
+                "no session token, set {SESSION_VAR} or put it in {SESSION_FILE}"
+            ),
+            ClientError::Status(code, body) => {
+                write!(f, "server answered {code}: {0}", body.trim())
+            }
+            ClientError::Transport(e) => write!(f, "request failed: {e}"),
+            ClientError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<std::io::Error> for ClientError {
+    fn from(e: std::io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(code, response) => {
+                ClientError::Status(code, response.into_string().unwrap_or_default())
+            }
+            ureq::Error::Transport(e) => ClientError::Transport(e.to_string()),
+        }
+    }
+}
+
+/// Read the session token from [`SESSION_VAR`], or from `file` if it is not set.
+pub fn load_session(file: &Path) -> Result<String, ClientError> {
+    let token = match std::env::var(SESSION_VAR) {
+        Ok(token) => token,
+        Err(_) => fs::read_to_string(file).map_err(|_| ClientError::NoSession)?,
+    };
+
+    match token.trim() {
+        "" => Err(ClientError::NoSession),
+        token => Ok(token.to_string()),
+    }
+}
+
+/// Talks to the Advent of Code website, or whatever stands in for it.
+pub struct Client {
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        Client {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: session.into(),
+        }
+    }
+
+    /// The base URL from [`BASE_URL_VAR`] if set, [`DEFAULT_BASE_URL`] otherwise.
+    pub fn base_url_from_env() -> String {
+        std::env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+    }
+
+    fn get(&self, path: &str) -> Result<String, ClientError> {
+        let mut body = String::new();
+        ureq::get(&format!("{0}{path}", self.base_url))
+            .set("User-Agent", USER_AGENT)
+            .set("Cookie", &format!("session={0}", self.session))
+            .call()?
+            .into_reader()
+            .read_to_string(&mut body)?;
+        Ok(body)
+    }
+
+    fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, ClientError> {
+        let mut body = String::new();
+        ureq::post(&format!("{0}{path}", self.base_url))
+            .set("User-Agent", USER_AGENT)
+            .set("Cookie", &format!("session={0}", self.session))
+            .send_form(form)?
+            .into_reader()
+            .read_to_string(&mut body)?;
+        Ok(body)
+    }
+
+    /// Download the puzzle input of a day.
+    pub fn input(&self, day: u32) -> Result<String, ClientError> {
+        self.get(&format!("/{YEAR}/day/{day}/input"))
+    }
+
+    /// Post an answer, returning the page the server answers with.
+    pub fn submit(&self, day: u32, part: u8, answer: &str) -> Result<String, ClientError> {
+        self.post(
+            &format!("/{YEAR}/day/{day}/answer"),
+            &[("level", &part.to_string()), ("answer", answer)],
+        )
+    }
+}
+
+/// Download the puzzle input of a day to `path`, unless it is already there.
+/// Returns whether it was downloaded.
+pub fn fetch(client: &Client, day: u32, path: &Path) -> Result<bool, ClientError> {
+    if path.exists() {
+        return Ok(false);
+    }
+
+    let input = client.input(day)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write then rename, so that an interrupted download doesn't look cached
+    let partial = PathBuf::from(format!("{0}.partial", path.display()));
+    fs::write(&partial, input)?;
+    fs::rename(&partial, path)?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+pub mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// A request as seen by the [`serve`] mock server.
+    pub struct Request {
+        pub method: String,
+        pub path: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Serve one canned response per request on a local port, then hand back
+    /// the requests that were made. Returns the base URL to use.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{0}", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let mut words = line.split_whitespace();
+                let method = words.next().unwrap_or_default().to_string();
+                let path = words.next().unwrap_or_default().to_string();
+
+                let mut headers = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    match line.trim_end().split_once(": ") {
+                        Some((name, value)) => headers.push((name.to_string(), value.to_string())),
+                        None => break,
+                    }
+                }
+
+                let mut request = Request {
+                    method,
+                    path,
+                    headers,
+                    body: String::new(),
+                };
+                let length = request
+                    .header("Content-Length")
+                    .map_or(0, |l| l.parse().unwrap());
+                let mut sent = vec![0; length];
+                reader.read_exact(&mut sent).unwrap();
+                request.body = String::from_utf8(sent).unwrap();
+                requests.push(request);
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Whatever\r\nContent-Length: {0}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{fetch, mock, Client, ClientError};
+    use std::fs;
+
+    #[test]
+    fn test_fetch() {
+        let (url, server) = mock::serve(vec![(200, "1 2 3\n")]);
+        let client = Client::new(url, "abc");
+        let dir = std::env::temp_dir().join(format!("aoc2023-fetch-{0}", std::process::id()));
+        let path = dir.join("day4.puzzle");
+
+        assert!(fetch(&client, 4, &path).unwrap());
+        // Cached, the server would not answer a second time
+        assert!(!fetch(&client, 4, &path).unwrap());
+        assert_eq!("1 2 3\n", fs::read_to_string(&path).unwrap());
+
+        let requests = server.join().unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!("GET", requests[0].method);
+        assert_eq!("/2023/day/4/input", requests[0].path);
+        assert_eq!(Some("session=abc"), requests[0].header("Cookie"));
+        let user_agent = requests[0].header("User-Agent").unwrap();
+        assert!(user_agent.starts_with("aoc2023/"));
+        assert!(user_agent.ends_with(" (+https://github.com/SamuelYvon/aoc2023)"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error() {
+        let (url, _) = mock::serve(vec![(400, "Please log in")]);
+        let client = Client::new(url, "expired");
+        let path =
+            std::env::temp_dir().join(format!("aoc2023-{0}-day1.puzzle", std::process::id()));
+
+        match fetch(&client, 1, &path) {
+            Err(ClientError::Status(400, body)) => assert_eq!("Please log in", body),
+            other => panic!("Unexpected {other:?}"),
+        }
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use utils::{Answer, AnswerStore, InputLayout};
+
+/// What a puzzle page holds that is worth keeping.
+#[derive(Debug, PartialEq)]
+pub struct Page {
+    /// Content of every `<pre><code>` block, in order
+    pub blocks: Vec<String>,
+    /// Answer given in the text of each part, with the block it is the answer
+    /// for: the last one before it
+    pub answers: Vec<(u8, Option<usize>, String)>,
+}
+
+const BLOCK_START: &str = "<pre><code>";
+const BLOCK_END: &str = "</code></pre>";
+const ANSWER_START: &str = "<code><em>";
+const ANSWER_END: &str = "</em></code>";
+
+/// Text of an HTML fragment, without its tags and with the usual entities
+/// decoded. Whitespace is kept as is.
+fn unescape(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Read the example blocks and the answers to them out of a puzzle page. Each
+/// part of the puzzle is an `<article>`, whose answer is the last emphasized
+/// code in it.
+pub fn parse_page(html: &str) -> Page {
+    let mut blocks = vec![];
+    let mut answers = vec![];
+
+    for (i, article) in html.split("<article").skip(1).enumerate() {
+        let article = article.split("</article>").next().unwrap_or(article);
+        let mut answer = None;
+
+        let mut rest = article;
+        loop {
+            let block = rest.find(BLOCK_START);
+            let emphasized = rest.find(ANSWER_START);
+
+            let (start, open, close) = match (block, emphasized) {
+                (Some(b), Some(e)) if b < e => (b, BLOCK_START, BLOCK_END),
+                (Some(b), None) => (b, BLOCK_START, BLOCK_END),
+                (_, Some(e)) => (e, ANSWER_START, ANSWER_END),
+                (None, None) => break,
+            };
+
+            let content = &rest[start + open.len()..];
+            let Some(end) = content.find(close) else {
+                break;
+            };
+
+            let text = unescape(&content[..end]);
+            if open == BLOCK_START {
+                blocks.push(text);
+            } else {
+                answer = Some(text);
+            }
+            rest = &content[end + close.len()..];
+        }
+
+        if let Some(answer) = answer {
+            answers.push((i as u8 + 1, blocks.len().checked_sub(1), answer));
+        }
+    }
+
+    Page { blocks, answers }
+}
+
+/// Ask which blocks to keep, as 1-based numbers.
+fn ask_pick(page: &Page) -> Vec<usize> {
+    for (i, block) in page.blocks.iter().enumerate() {
+        println!("--- {0} ({1} lines)", i + 1, block.lines().count());
+        for line in block.lines().take(5) {
+            println!("{line}");
+        }
+        if block.lines().count() > 5 {
+            println!("...");
+        }
+    }
+
+    print!("Blocks to save as examples, e.g. `1 3` (none if empty): ");
+    std::io::stdout().flush().ok();
+
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line).ok();
+    line.split([' ', ','])
+        .filter_map(|n| n.trim().parse().ok())
+        .collect()
+}
+
+/// Number after the highest numbered example of `day` already there, for
+/// either part, so that extracting again adds examples instead of replacing
+/// them.
+fn next_example(layout: &InputLayout, day: u32) -> usize {
+    (1..=2)
+        .flat_map(|part| layout.kinds(day, part, "example"))
+        .filter_map(|kind| kind.strip_prefix("example")?.parse::<usize>().ok())
+        .max()
+        .map_or(1, |n| n + 1)
+}
+
+/// Save the picked blocks of `page` as the numbered examples of `day`, after
+/// the ones already there, and the answers to them in `store`. Blocks are
+/// picked interactively unless `pick` says which. Returns the files written.
+pub fn save_examples(
+    day: u32,
+    page: &Page,
+    pick: Option<Vec<usize>>,
+    layout: &InputLayout,
+    store: &mut AnswerStore,
+) -> std::io::Result<Vec<PathBuf>> {
+    let pick = pick.unwrap_or_else(|| ask_pick(page));
+    let first = next_example(layout, day);
+
+    let mut written = vec![];
+    for (k, &n) in pick.iter().enumerate() {
+        let Some(block) = n.checked_sub(1).and_then(|i| page.blocks.get(i)) else {
+            eprintln!("There is no block {n}, skipping it");
+            continue;
+        };
+
+        let kind = format!("example{0}", first + k);
+        let path = layout.day_path(day, &kind);
+        std::fs::create_dir_all(layout.dir())?;
+        std::fs::write(&path, block)?;
+        written.push(path);
+
+        for (part, _, answer) in page.answers.iter().filter(|a| a.1 == Some(n - 1)) {
+            let answer = match answer.parse::<i64>() {
+                Ok(i) => Answer::Int(i),
+                Err(_) => Answer::from(answer.as_str()),
+            };
+            store.set(day, *part, &kind, &answer)?;
+        }
+    }
+
+    if !written.is_empty() {
+        store.save()?;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extract::{parse_page, save_examples, unescape};
+    use utils::{Answer, AnswerStore, InputLayout};
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 6: Wait For It ---</h2><p>For example:</p>
+<pre><code>Time:      7  15   30
+Distance:  9  40  200
+</code></pre>
+<p>In this example, if you multiply these values together, you get <code><em>288</em></code> (<code>4</code> * <code>8</code> * <code>9</code>).</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>So, the example from before now becomes <code>71530</code>, and there are <code><em>71503</em></code> ways to win.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_parse_page() {
+        let page = parse_page(PAGE);
+
+        assert_eq!(
+            vec!["Time:      7  15   30\nDistance:  9  40  200\n".to_string()],
+            page.blocks
+        );
+        assert_eq!(
+            vec![
+                (1, Some(0), "288".to_string()),
+                (2, Some(0), "71503".to_string())
+            ],
+            page.answers
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            "a<b> & \"c\"",
+            unescape("<em>a&lt;b&gt;</em> &amp; &quot;c&quot;")
+        );
+        assert_eq!("&lt;", unescape("&amp;lt;"));
+    }
+
+    #[test]
+    fn test_extract_again() {
+        let dir = std::env::temp_dir().join(format!("aoc2023-extract-{0}", std::process::id()));
+        let layout = InputLayout::new(&dir);
+        let mut store = AnswerStore::load(dir.join("answers.toml")).unwrap();
+        let page = parse_page(PAGE);
+
+        // Once with part 1 only, then again once part 2 is unlocked
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(layout.day_path(6, "example1"), "kept").unwrap();
+        store.set(6, 1, "example1", &Answer::Int(1)).unwrap();
+        let written = save_examples(6, &page, Some(vec![1]), &layout, &mut store).unwrap();
+        assert_eq!(vec![layout.day_path(6, "example2")], written);
+        save_examples(6, &page, Some(vec![1]), &layout, &mut store).unwrap();
+
+        let kept = std::fs::read_to_string(layout.day_path(6, "example1")).unwrap();
+        assert_eq!("kept", kept);
+        assert_eq!(Some(Answer::Int(1)), store.get(6, 1, "example1"));
+        assert_eq!(Some(Answer::Int(288)), store.get(6, 1, "example2"));
+        assert_eq!(Some(Answer::Int(71503)), store.get(6, 2, "example3"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
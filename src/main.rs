@@ -1,72 +1,27 @@
-use std::ops::Deref;
-use std::process::exit;
-use std::str::FromStr;
-use utils::Problem;
-
-pub enum Days {
-    Day1,
-    Day2,
-    Day3,
-    Day4,
-    Day5,
-    Day6,
-}
-
-impl FromStr for Days {
-    type Err = ();
+mod alloc;
+mod bench;
+mod cli;
+mod client;
+mod extract;
+mod report;
+mod runner;
+mod scaffold;
+mod submit;
+mod verify;
+mod watch;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase().deref() {
-            "day1" => Ok(Days::Day1),
-            "day2" => Ok(Days::Day2),
-            "day3" => Ok(Days::Day3),
-            "day4" => Ok(Days::Day4),
-            "day5" => Ok(Days::Day5),
-            "day6" => Ok(Days::Day6),
-            _ => Err(()),
-        }
-    }
-}
+use cli::Command;
+use client::Client;
+use report::Format;
+use runner::Outcome;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use submit::{History, Verdict};
+use utils::{AnswerStore, InputSource, Level, ParseError};
 
-impl Days {
-    fn run(&self, part: u8, debug: bool) {
-        let problem: Box<dyn Problem> = match self {
-            // Annoying that I have to box it, and I could do it with unsafe
-            // if I wanted to, but meh here.
-            Days::Day1 => {
-                use day1::DAY_1;
-                Box::new(DAY_1)
-            }
-            Days::Day2 => {
-                use day2::DAY_2;
-                Box::new(DAY_2)
-            }
-            Days::Day3 => {
-                use day3::DAY_3;
-                Box::new(DAY_3)
-            }
-            Days::Day4 => {
-                use day4::DAY_4;
-                Box::new(DAY_4)
-            }
-            Days::Day5 => {
-                use day5::DAY_5;
-                Box::new(DAY_5)
-            }
-            Days::Day6 => {
-                use day6::DAY_6;
-                Box::new(DAY_6)
-            }
-        };
-
-        if part == 1 {
-            problem.get_part1()(debug);
-        } else if part == 2 {
-            problem.get_part2()(debug);
-        } else {
-            panic!("Invalid problem part: {0}", part);
-        }
-    }
+mod registry {
+    include!(concat!(env!("OUT_DIR"), "/days.rs"));
 }
 
 enum ProblemType {
@@ -75,6 +30,15 @@ enum ProblemType {
 }
 
 impl ProblemType {
+    pub const ALL: [ProblemType; 2] = [ProblemType::Example, ProblemType::Puzzle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProblemType::Example => "example",
+            ProblemType::Puzzle => "puzzle",
+        }
+    }
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_ascii_lowercase().deref() {
             "example" => Some(Self::Example),
@@ -84,31 +48,244 @@ impl ProblemType {
     }
 }
 
-fn main() {
-    let args: Vec<String> = std::env::args().skip(1).collect();
+/// Where to write the spans, when tracing.
+static TRACE_FILE: OnceLock<PathBuf> = OnceLock::new();
 
-    if args.len() != 3 {
-        eprintln!("Usage: ./aoc2023 DAY PART TYPE");
-        exit(1);
+/// Write the trace, if any, and exit.
+fn exit(code: i32) -> ! {
+    if let Some(path) = TRACE_FILE.get() {
+        let trace = utils::chrome_trace(&utils::finish_trace());
+        if let Err(e) = std::fs::write(path, trace) {
+            eprintln!("Could not write the trace to {0}: {e}", path.display());
+        }
     }
+    std::process::exit(code)
+}
+
+/// Exit code when an input could not be parsed.
+const PARSE_ERROR_EXIT_CODE: i32 = 2;
+
+fn read_input(source: &InputSource) -> String {
+    source.read().unwrap_or_else(|e| {
+        eprintln!("Could not read {source}: {e}");
+        exit(1);
+    })
+}
 
-    let part: u8 =
-        u8::from_str(&args[1]).unwrap_or_else(|_| panic!("Invalid part, got {0}", args[1]));
-    if part != 1 && part != 2 {
-        eprintln!("Part must be one or 2");
+fn load_answers(path: &Path) -> AnswerStore {
+    AnswerStore::load(path).unwrap_or_else(|e| {
+        eprintln!("Could not load {0}: {e}", path.display());
         exit(1);
+    })
+}
+
+fn parse_failed(e: &ParseError, source: &InputSource, input: &str) -> ! {
+    eprintln!("{}", e.render(&source.to_string(), input));
+    exit(PARSE_ERROR_EXIT_CODE);
+}
+
+fn main() {
+    let args = cli::parse(std::env::args().skip(1).collect()).unwrap_or_else(|e| {
+        eprintln!("{e}\n\n{0}", cli::USAGE);
+        exit(1);
+    });
+
+    utils::set_log_level(Level::Warn.shifted(args.verbosity));
+
+    if let Some(path) = args.trace {
+        TRACE_FILE.get_or_init(|| path);
+        utils::start_trace();
     }
 
-    let type_ = &args[2];
-    let type_ = ProblemType::from_str(type_)
-        .unwrap_or_else(|| panic!("Not a valid problem type. Got {type_}"));
+    match args.command {
+        Command::All => {
+            let all_ok = runner::run_all(&args.layout, args.format, args.sequential);
+            exit(if all_ok { 0 } else { 1 });
+        }
+        Command::List => runner::list(&args.layout),
+        Command::Verify { day } => {
+            let store = load_answers(&args.answers);
+
+            let all_ok = verify::verify(&args.layout, &store, day, args.sequential);
+            exit(if all_ok { 0 } else { 1 });
+        }
+        Command::Bench {
+            day,
+            parts,
+            options,
+        } => {
+            // Stdin can only be read once, so parts with the same source
+            // share the input read for the first one
+            let mut inputs: Vec<(InputSource, String)> = vec![];
+            for (part, source) in parts {
+                let i = match inputs.iter().position(|(read, _)| *read == source) {
+                    Some(i) => i,
+                    None => {
+                        inputs.push((source.clone(), read_input(&source)));
+                        inputs.len() - 1
+                    }
+                };
+                let input = &inputs[i].1;
+                if let Err(e) = bench::bench(day, part, &source, input, &options) {
+                    parse_failed(&e, &source, input);
+                }
+            }
+        }
+        Command::Fetch { day } => {
+            let path = args.layout.day_path(day, ProblemType::Puzzle.name());
+            let downloaded = client::load_session(Path::new(client::SESSION_FILE))
+                .map(|session| Client::new(args.base_url, session))
+                .and_then(|client| client::fetch(&client, day, &path))
+                .unwrap_or_else(|e| {
+                    eprintln!("Could not fetch the input of day{day}: {e}");
+                    exit(1);
+                });
+
+            if downloaded {
+                println!("Downloaded {0}", path.display());
+            } else {
+                println!("{0} is already there", path.display());
+            }
+        }
+        Command::Extract { day, page, pick } => {
+            let html = std::fs::read_to_string(&page).unwrap_or_else(|e| {
+                eprintln!("Could not read {0}: {e}", page.display());
+                exit(1);
+            });
+            let page = extract::parse_page(&html);
+            if page.blocks.is_empty() {
+                eprintln!("No example blocks found in the page");
+                exit(1);
+            }
+
+            let mut store = load_answers(&args.answers);
+            let written = extract::save_examples(day, &page, pick, &args.layout, &mut store)
+                .unwrap_or_else(|e| {
+                    eprintln!("Could not save the examples: {e}");
+                    exit(1);
+                });
+
+            for path in written {
+                println!("Saved {0}", path.display());
+            }
+        }
+        Command::New { day } => {
+            let created =
+                scaffold::new_day(Path::new("."), &args.layout, day).unwrap_or_else(|e| {
+                    eprintln!("Could not create day{day}: {e}");
+                    exit(1);
+                });
+
+            for path in created {
+                println!("Created {0}", path.display());
+            }
+            println!("Added day{day} to Cargo.toml, it will be picked up on the next build");
+        }
+        Command::Submit { day, part, source } => {
+            let input = read_input(&source);
+            let answer = day
+                .run(part, &input)
+                .unwrap_or_else(|e| parse_failed(&e, &source, &input))
+                .answer;
+            if answer.is_multiline() {
+                eprintln!("Multiline answers can't be submitted:\n{answer}");
+                exit(1);
+            }
+
+            let fail = |what: &str, e: &dyn std::fmt::Display| -> ! {
+                eprintln!("Could not {what}: {e}");
+                exit(1);
+            };
 
-    let debug = match type_ {
-        ProblemType::Example => true,
-        ProblemType::Puzzle => false,
-    };
+            let mut history = History::load(&args.history)
+                .unwrap_or_else(|e| fail(&format!("load {0}", args.history.display()), &e));
+            if let Some(reason) = history.rejects(day.number, part, &answer, submit::now()) {
+                println!("Not submitting, {reason}");
+                exit(1);
+            }
+
+            let page = client::load_session(Path::new(client::SESSION_FILE))
+                .map(|session| Client::new(args.base_url, session))
+                .and_then(|client| client.submit(day.number, part, &answer.to_string()))
+                .unwrap_or_else(|e| fail("submit", &e));
+            let verdict = Verdict::from_page(&page);
+            let wait = submit::wait_from_page(&page);
+
+            history
+                .record(day.number, part, &answer, &verdict, wait)
+                .and_then(|_| history.save())
+                .unwrap_or_else(|e| fail(&format!("save {0}", history.path().display()), &e));
+            println!("Answer {answer} for {0} part {part}: {verdict}", day.name);
+            if let (Some(seconds), false) = (wait, matches!(verdict, Verdict::Wait(_))) {
+                println!("The next answer can be sent in {seconds}s");
+            }
+
+            if verdict != Verdict::Correct {
+                exit(1);
+            }
+
+            // Keep it around for verify
+            let mut store = AnswerStore::load(&args.answers)
+                .unwrap_or_else(|e| fail(&format!("load {0}", args.answers.display()), &e));
+            store
+                .set(day.number, part, ProblemType::Puzzle.name(), &answer)
+                .and_then(|_| store.save())
+                .unwrap_or_else(|e| fail(&format!("save {0}", store.path().display()), &e));
+        }
+        Command::Run {
+            day,
+            part,
+            source,
+            kind,
+            ..
+        } if args.format != Format::Text => {
+            let record = runner::record(day, part, kind, source);
+            runner::report(std::slice::from_ref(&record), args.format);
+
+            match record.outcome {
+                Outcome::Solved(_) => {}
+                Outcome::ParseFailed(_) => exit(PARSE_ERROR_EXIT_CODE),
+                _ => exit(1),
+            }
+        }
+        Command::Examples { day, part, kinds } if args.format != Format::Text => {
+            let _quiet = runner::silence_panics();
+            let records = runner::map_jobs(&kinds, args.sequential, |kind| {
+                let source = InputSource::File(args.layout.path(day.number, part, kind));
+                runner::record(day, part, kind.clone(), source)
+            });
+            runner::report(&records, args.format);
+
+            let all_ok = records
+                .iter()
+                .all(|record| matches!(record.outcome, Outcome::Solved(_)));
+            exit(if all_ok { 0 } else { 1 });
+        }
+        Command::Examples { day, part, kinds } => {
+            let store = load_answers(&args.answers);
+            let all_ok =
+                verify::verify_part(&args.layout, &store, day, part, &kinds, args.sequential);
+            exit(if all_ok { 0 } else { 1 });
+        }
+        Command::Run {
+            day,
+            part,
+            source: InputSource::File(path),
+            kind,
+            watch: true,
+        } => watch::watch(day, part, &path, &kind, &args.layout, &args.answers),
+        Command::Run {
+            day, part, source, ..
+        } => {
+            let input = read_input(&source);
+            let (result, alloc) = alloc::measure(|| day.run(part, &input));
+            let run = result.unwrap_or_else(|e| parse_failed(&e, &source, &input));
+            runner::print_run(&run);
+            if let Some(stats) = alloc {
+                println!("Memory: {stats}");
+            }
+        }
+    }
 
-    let day = &args[0];
-    let day = Days::from_str(day).unwrap_or_else(|_| panic!("Not a valid day entered. Got {day}"));
-    day.run(part, debug);
+    exit(0);
 }
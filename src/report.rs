@@ -0,0 +1,206 @@
+use crate::alloc::AllocStats;
+use crate::runner::Outcome;
+use utils::{json_string, Answer};
+
+/// How the results of runs are printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// One run of one part of a day, as reported by the machine readable formats.
+pub struct Record {
+    pub day: u32,
+    pub part: u8,
+    /// Kind of input (`example`, `puzzle`), or `custom` when given explicitly
+    pub input: String,
+    pub outcome: Outcome,
+    /// Heap usage of the run, when the tracking allocator is compiled in
+    pub alloc: Option<AllocStats>,
+}
+
+impl Record {
+    fn status(&self) -> &'static str {
+        match self.outcome {
+            Outcome::Solved(_) => "ok",
+            Outcome::MissingInput(_) => "missing_input",
+            Outcome::ParseFailed(_) => "parse_error",
+            Outcome::Panicked(_) => "panicked",
+        }
+    }
+
+    fn answer(&self) -> Option<&Answer> {
+        match &self.outcome {
+            Outcome::Solved(run) => Some(&run.answer),
+            _ => None,
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        match &self.outcome {
+            Outcome::Solved(_) => None,
+            Outcome::MissingInput(source) => Some(format!("could not read {source}")),
+            Outcome::ParseFailed(e) => Some(format!("line {0}, column {1}: {e}", e.line, e.column)),
+            Outcome::Panicked(message) => Some(message.clone()),
+        }
+    }
+
+    /// Parse and solve durations, in nanoseconds.
+    fn durations(&self) -> Option<(u128, u128)> {
+        match &self.outcome {
+            Outcome::Solved(run) => Some((run.parse_time.as_nanos(), run.solve_time.as_nanos())),
+            _ => None,
+        }
+    }
+}
+
+fn json_or_null<T>(value: Option<T>, f: impl Fn(T) -> String) -> String {
+    value.map(f).unwrap_or_else(|| "null".to_string())
+}
+
+/// Records as a JSON array, one record per line, with the keys always in the
+/// same order so that outputs can be diffed.
+pub fn to_json(records: &[Record]) -> String {
+    let lines: Vec<String> = records
+        .iter()
+        .map(|record| {
+            let answer = json_or_null(record.answer(), |answer| match answer {
+                Answer::Int(i) => i.to_string(),
+                other => json_string(&other.to_string()),
+            });
+            let durations = record.durations();
+
+            format!(
+                "  {{\"day\": {0}, \"part\": {1}, \"input\": {2}, \"status\": {3}, \"answer\": {4}, \"parse_ns\": {5}, \"solve_ns\": {6}, \"allocs\": {7}, \"alloc_bytes\": {8}, \"peak_bytes\": {9}, \"error\": {10}}}",
+                record.day,
+                record.part,
+                json_string(&record.input),
+                json_string(record.status()),
+                answer,
+                json_or_null(durations, |(parse, _)| parse.to_string()),
+                json_or_null(durations, |(_, solve)| solve.to_string()),
+                json_or_null(record.alloc, |stats| stats.count.to_string()),
+                json_or_null(record.alloc, |stats| stats.bytes.to_string()),
+                json_or_null(record.alloc, |stats| stats.peak.to_string()),
+                json_or_null(record.error(), |e| json_string(&e)),
+            )
+        })
+        .collect();
+
+    if lines.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", lines.join(",\n"))
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Records as CSV, with a header line.
+pub fn to_csv(records: &[Record]) -> String {
+    let mut out = String::from(
+        "day,part,input,status,answer,parse_ns,solve_ns,allocs,alloc_bytes,peak_bytes,error\n",
+    );
+
+    for record in records {
+        let durations = record.durations();
+        let alloc = |f: fn(AllocStats) -> u64| {
+            record
+                .alloc
+                .map(|stats| f(stats).to_string())
+                .unwrap_or_default()
+        };
+        let fields = [
+            record.day.to_string(),
+            record.part.to_string(),
+            csv_field(&record.input),
+            record.status().to_string(),
+            csv_field(&record.answer().map(|a| a.to_string()).unwrap_or_default()),
+            durations.map(|(p, _)| p.to_string()).unwrap_or_default(),
+            durations.map(|(_, s)| s.to_string()).unwrap_or_default(),
+            alloc(|stats| stats.count),
+            alloc(|stats| stats.bytes),
+            alloc(|stats| stats.peak),
+            csv_field(&record.error().unwrap_or_default()),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alloc::AllocStats;
+    use crate::report::{to_csv, to_json, Record};
+    use crate::runner::Outcome;
+    use std::time::Duration;
+    use utils::{Answer, Run};
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 3,
+                part: 1,
+                input: "example".to_string(),
+                outcome: Outcome::Solved(Run {
+                    answer: Answer::Int(4361),
+                    parse_time: Duration::from_nanos(1500),
+                    solve_time: Duration::from_nanos(42),
+                }),
+                alloc: Some(AllocStats {
+                    count: 12,
+                    bytes: 4096,
+                    peak: 1024,
+                }),
+            },
+            Record {
+                day: 3,
+                part: 2,
+                input: "example".to_string(),
+                outcome: Outcome::Panicked("found no \"number\", oops".to_string()),
+                alloc: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_json() {
+        let expected = r#"[
+  {"day": 3, "part": 1, "input": "example", "status": "ok", "answer": 4361, "parse_ns": 1500, "solve_ns": 42, "allocs": 12, "alloc_bytes": 4096, "peak_bytes": 1024, "error": null},
+  {"day": 3, "part": 2, "input": "example", "status": "panicked", "answer": null, "parse_ns": null, "solve_ns": null, "allocs": null, "alloc_bytes": null, "peak_bytes": null, "error": "found no \"number\", oops"}
+]
+"#;
+        assert_eq!(expected, to_json(&records()));
+        assert_eq!("[]\n", to_json(&[]));
+    }
+
+    #[test]
+    fn test_csv() {
+        let expected = r#"day,part,input,status,answer,parse_ns,solve_ns,allocs,alloc_bytes,peak_bytes,error
+3,1,example,ok,4361,1500,42,12,4096,1024,
+3,2,example,panicked,,,,,,,"found no ""number"", oops"
+"#;
+        assert_eq!(expected, to_csv(&records()));
+    }
+}
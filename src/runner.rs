@@ -0,0 +1,319 @@
+use crate::alloc::{self, TRACKING};
+use crate::registry::DAYS;
+use crate::report::{to_csv, to_json, Format, Record};
+use crate::ProblemType;
+use std::panic::{catch_unwind, set_hook, take_hook, AssertUnwindSafe, PanicHookInfo};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{available_parallelism, scope};
+use std::time::Duration;
+use utils::{span_with, Day, InputLayout, InputSource, ParseError, Run};
+
+/// What happened when trying to run a single day/part/input combination.
+pub enum Outcome {
+    Solved(Run),
+    MissingInput(InputSource),
+    ParseFailed(ParseError),
+    Panicked(String),
+}
+
+impl Outcome {
+    pub fn elapsed(&self) -> Option<Duration> {
+        match self {
+            Outcome::Solved(run) => Some(run.parse_time + run.solve_time),
+            _ => None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Outcome::Solved(run) => {
+                let answer = run.answer.to_string();
+                match answer.lines().count() {
+                    0 | 1 => answer,
+                    n => format!("{0} (+{1} lines)", answer.lines().next().unwrap(), n - 1),
+                }
+            }
+            Outcome::MissingInput(source) => format!("missing input {source}"),
+            Outcome::ParseFailed(e) => {
+                format!("parse error at {0}:{1}: {e}", e.line, e.column)
+            }
+            Outcome::Panicked(message) => format!("panicked: {message}"),
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo) + Sync + Send>;
+
+/// Keeps panics from being printed for as long as it lives, for when they are
+/// reported some other way.
+pub struct SilencedPanics(Option<PanicHook>);
+
+pub fn silence_panics() -> SilencedPanics {
+    let hook = take_hook();
+    set_hook(Box::new(|_| {}));
+    SilencedPanics(Some(hook))
+}
+
+impl Drop for SilencedPanics {
+    fn drop(&mut self) {
+        if let Some(hook) = self.0.take() {
+            set_hook(hook);
+        }
+    }
+}
+
+/// Run one part of a day, turning a missing input or a panic into an [`Outcome`]
+/// instead of bringing the whole program down.
+pub fn try_run(day: &Day, part: u8, source: InputSource) -> Outcome {
+    match source.read() {
+        Ok(input) => try_solve(day, part, &input),
+        Err(_) => Outcome::MissingInput(source),
+    }
+}
+
+/// Run one part of a day on an input already read, turning a panic into an
+/// [`Outcome`].
+pub fn try_solve(day: &Day, part: u8, input: &str) -> Outcome {
+    let _s = span_with(|| format!("{0} part{part}", day.name));
+    match catch_unwind(AssertUnwindSafe(|| day.run(part, input))) {
+        Ok(Ok(run)) => Outcome::Solved(run),
+        Ok(Err(e)) => Outcome::ParseFailed(e),
+        Err(payload) => Outcome::Panicked(panic_message(payload)),
+    }
+}
+
+/// Run one part of a day like [`try_run`], measuring the heap usage of the
+/// solving when the tracking allocator is compiled in.
+pub fn record(day: &Day, part: u8, input: String, source: InputSource) -> Record {
+    let (outcome, alloc) = match source.read() {
+        Ok(content) => alloc::measure(|| try_solve(day, part, &content)),
+        Err(_) => (Outcome::MissingInput(source), None),
+    };
+
+    Record {
+        day: day.number,
+        part,
+        input,
+        outcome,
+        alloc,
+    }
+}
+
+/// Apply `f` to every job, spread over all the cores unless `sequential`, and
+/// return the results in the order of the jobs.
+pub fn map_jobs<T: Sync, R: Send>(
+    jobs: &[T],
+    sequential: bool,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let threads = match sequential {
+        true => 1,
+        false => available_parallelism().map_or(1, |n| n.get()),
+    };
+    map_jobs_on(jobs, threads, f)
+}
+
+fn map_jobs_on<T: Sync, R: Send>(jobs: &[T], threads: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    if threads.min(jobs.len()) <= 1 {
+        return jobs.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = jobs.iter().map(|_| None).collect();
+
+    scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(jobs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(job) = jobs.get(i) else {
+                            break;
+                        };
+                        done.push((i, f(job)));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (i, result) in worker.join().expect("A worker thread panicked") {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    results.into_iter().map(|r| r.unwrap()).collect()
+}
+
+/// Print the answer of a single run and how long it took.
+pub fn print_run(run: &Run) {
+    let answer = &run.answer;
+    if answer.is_multiline() {
+        println!("Answer:\n{answer}");
+    } else {
+        println!("Answer: {answer}");
+    }
+
+    println!(
+        "Parsed in {:?}, solved in {:?}",
+        run.parse_time, run.solve_time
+    );
+}
+
+/// Day, part, type, answer and elapsed time of a run for the text table.
+fn text_row(record: &Record) -> [String; 5] {
+    [
+        record.day.to_string(),
+        record.part.to_string(),
+        record.input.clone(),
+        record.outcome.summary(),
+        record
+            .outcome
+            .elapsed()
+            .map(|d| format!("{d:.2?}"))
+            .unwrap_or_else(|| "-".to_string()),
+    ]
+}
+
+/// Print the outcome of runs in the given format.
+pub fn report(records: &[Record], format: Format) {
+    let header = ["Day", "Part", "Type", "Answer", "Elapsed"];
+    match format {
+        Format::Text if TRACKING => {
+            let rows: Vec<_> = records
+                .iter()
+                .map(|record| {
+                    let [day, part, input, answer, elapsed] = text_row(record);
+                    let memory = record
+                        .alloc
+                        .map_or_else(|| "-".to_string(), |stats| stats.to_string());
+                    [day, part, input, answer, elapsed, memory]
+                })
+                .collect();
+            let [day, part, input, answer, elapsed] = header;
+            print_table(&[day, part, input, answer, elapsed, "Memory"], &rows);
+        }
+        Format::Text => {
+            let rows: Vec<_> = records.iter().map(text_row).collect();
+            print_table(&header, &rows);
+        }
+        Format::Json => print!("{}", to_json(records)),
+        Format::Csv => print!("{}", to_csv(records)),
+    }
+}
+
+/// Run both parts of every day on both inputs and report how it went.
+/// Returns whether everything ran to completion.
+pub fn run_all(layout: &InputLayout, format: Format, sequential: bool) -> bool {
+    // The panics are reported in the table, don't spam stderr with them
+    let _quiet = silence_panics();
+
+    let mut jobs = vec![];
+    for day in DAYS.iter() {
+        for part in 1..=2 {
+            for type_ in ProblemType::ALL.iter() {
+                for kind in layout.kinds(day.number, part, type_.name()) {
+                    jobs.push((day, part, kind));
+                }
+            }
+        }
+    }
+
+    let records = map_jobs(&jobs, sequential, |(day, part, kind)| {
+        let source = InputSource::File(layout.path(day.number, *part, kind));
+        record(day, *part, kind.clone(), source)
+    });
+
+    report(&records, format);
+    records
+        .iter()
+        .all(|record| matches!(record.outcome, Outcome::Solved(_)))
+}
+
+/// Print every registered day along with the inputs found for it.
+pub fn list(layout: &InputLayout) {
+    let rows: Vec<_> = DAYS
+        .iter()
+        .map(|day| {
+            let mut row = [day.name.to_string(), String::new(), String::new()];
+            for (cell, type_) in row[1..].iter_mut().zip(ProblemType::ALL.iter()) {
+                let found = (1..=2)
+                    .map(|part| {
+                        layout
+                            .kinds(day.number, part, type_.name())
+                            .iter()
+                            .filter(|kind| layout.path(day.number, part, kind).exists())
+                            .count()
+                    })
+                    .max()
+                    .unwrap_or(0);
+                *cell = match found {
+                    0 => "-".to_string(),
+                    1 => "yes".to_string(),
+                    n => format!("yes ({n})"),
+                };
+            }
+            row
+        })
+        .collect();
+
+    print_table(&["Day", "Example", "Puzzle"], &rows);
+}
+
+pub fn print_table<const N: usize>(header: &[&str; N], rows: &[[String; N]]) {
+    let mut widths = header.map(|h| h.len());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", line(header.to_vec()));
+    println!("{}", widths.map(|w| "-".repeat(w)).to_vec().join("-+-"));
+    for row in rows {
+        println!("{}", line(row.iter().map(|c| c.as_str()).collect()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::{map_jobs, map_jobs_on};
+
+    #[test]
+    fn test_map_jobs_keeps_order() {
+        let jobs: Vec<u64> = (0..100).collect();
+        let square = |n: &u64| {
+            // Uneven amounts of work, so that the jobs finish out of order
+            std::thread::sleep(std::time::Duration::from_micros((100 - n) * 10));
+            n * n
+        };
+
+        let expected: Vec<u64> = jobs.iter().map(|n| n * n).collect();
+        assert_eq!(expected, map_jobs_on(&jobs, 4, square));
+        assert_eq!(expected, map_jobs(&jobs, true, square));
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Array, DocumentMut, InlineTable};
+use utils::InputLayout;
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/day/Cargo.toml.template");
+const LIB_TEMPLATE: &str = include_str!("../templates/day/lib.rs.template");
+
+fn render(template: &str, day: u32) -> String {
+    template.replace("{{day}}", &day.to_string())
+}
+
+/// Sort key putting the `dayN` crates first, in numerical order.
+fn day_order(name: &str) -> (u8, u32, &str) {
+    match name.strip_prefix("day").and_then(|n| n.parse().ok()) {
+        Some(n) => (0, n, name),
+        None => (1, 0, name),
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, message.into())
+}
+
+/// Add the crate of a day to the workspace members and the runner dependencies
+/// of a `Cargo.toml`, keeping its formatting.
+fn add_to_manifest(manifest: &str, day: u32) -> Result<String> {
+    let name = format!("day{day}");
+    let mut doc: DocumentMut = manifest.parse().map_err(|e| invalid(format!("{e}")))?;
+
+    let members = doc["workspace"]["members"]
+        .as_array_mut()
+        .ok_or_else(|| invalid("Cargo.toml has no workspace members"))?;
+    if members.iter().any(|m| m.as_str() == Some(&name)) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{name} is already a workspace member"),
+        ));
+    }
+    let mut sorted: Vec<String> = members
+        .iter()
+        .filter_map(|m| m.as_str().map(str::to_string))
+        .chain([name.clone()])
+        .collect();
+    sorted.sort_by(|a, b| day_order(a).cmp(&day_order(b)));
+    *members = Array::from_iter(sorted);
+
+    let dependencies = doc["dependencies"]
+        .as_table_mut()
+        .ok_or_else(|| invalid("Cargo.toml has no dependencies"))?;
+    let mut dependency = InlineTable::new();
+    dependency.insert("path", format!("./{name}").into());
+    dependencies.insert(&name, value(dependency));
+    dependencies.sort_values_by(|a, _, b, _| day_order(a.get()).cmp(&day_order(b.get())));
+
+    Ok(doc.to_string())
+}
+
+/// Create the crate of a new day in the workspace at `root` from the template,
+/// register it with the runner and create its empty inputs. Returns the files
+/// that were created.
+pub fn new_day(root: &Path, layout: &InputLayout, day: u32) -> Result<Vec<PathBuf>> {
+    let dir = root.join(format!("day{day}"));
+    if dir.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{0} already exists", dir.display()),
+        ));
+    }
+
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = add_to_manifest(&fs::read_to_string(&manifest_path)?, day)?;
+
+    let mut created = vec![];
+    fs::create_dir_all(dir.join("src"))?;
+    for (path, template) in [
+        (dir.join("Cargo.toml"), CARGO_TEMPLATE),
+        (dir.join("src").join("lib.rs"), LIB_TEMPLATE),
+    ] {
+        fs::write(&path, render(template, day))?;
+        created.push(path);
+    }
+
+    fs::create_dir_all(root.join(layout.dir()))?;
+    for kind in ["example", "puzzle"] {
+        let path = root.join(layout.day_path(day, kind));
+        if !path.exists() {
+            fs::write(&path, "")?;
+            created.push(path);
+        }
+    }
+
+    // Last, so that a failure above doesn't leave a manifest pointing nowhere
+    fs::write(&manifest_path, manifest)?;
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scaffold::add_to_manifest;
+
+    const MANIFEST: &str = r#"[package]
+name = "aoc2023"
+
+[workspace]
+members = ["day1", "day2", "day10", "utils"]
+
+[dependencies]
+day1 = { path = "./day1" }
+day10 = { path = "./day10" }
+day2 = { path = "./day2" }
+toml = "0.8"
+utils = { path = "./utils" }
+"#;
+
+    #[test]
+    fn test_add_to_manifest() {
+        let expected = r#"[package]
+name = "aoc2023"
+
+[workspace]
+members = ["day1", "day2", "day7", "day10", "utils"]
+
+[dependencies]
+day1 = { path = "./day1" }
+day2 = { path = "./day2" }
+day7 = { path = "./day7" }
+day10 = { path = "./day10" }
+toml = "0.8"
+utils = { path = "./utils" }
+"#;
+        assert_eq!(expected, add_to_manifest(MANIFEST, 7).unwrap());
+    }
+
+    #[test]
+    fn test_add_existing_day() {
+        assert!(add_to_manifest(MANIFEST, 2).is_err());
+    }
+}
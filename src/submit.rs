@@ -0,0 +1,435 @@
+use std::fmt::{Display, Formatter};
+use std::io::Result;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+use utils::store::{day_part, day_part_entry, invalid_entry, load_doc};
+use utils::Answer;
+
+/// Default location of the submission history, relative to the working directory.
+pub const DEFAULT_HISTORY_FILE: &str = "submissions.toml";
+
+/// What the server made of a submitted answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Answered too recently, the answer was not looked at
+    Wait(u64),
+    /// Anything else, with the text of the page
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Read the verdict from the page answering a submission.
+    pub fn from_page(page: &str) -> Self {
+        let text = page_text(page);
+        let lower = text.to_ascii_lowercase();
+
+        if lower.contains("that's the right answer") {
+            Verdict::Correct
+        } else if lower.contains("answer too recently") {
+            Verdict::Wait(wait_seconds(&lower).unwrap_or(60))
+        } else if lower.contains("that's not the right answer") {
+            if lower.contains("too high") {
+                Verdict::TooHigh
+            } else if lower.contains("too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Incorrect
+            }
+        } else {
+            Verdict::Unknown(text)
+        }
+    }
+
+    /// Name of the verdict in the history.
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Incorrect => "incorrect",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::Wait(_) => "wait",
+            Verdict::Unknown(_) => "unknown",
+        }
+    }
+
+    /// The verdict called `name` in the history, `wait` being the seconds
+    /// that were left to wait when it is [`Verdict::Wait`].
+    fn from_name(name: &str, wait: u64) -> Option<Self> {
+        match name {
+            "correct" => Some(Verdict::Correct),
+            "incorrect" => Some(Verdict::Incorrect),
+            "too_high" => Some(Verdict::TooHigh),
+            "too_low" => Some(Verdict::TooLow),
+            "wait" => Some(Verdict::Wait(wait)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Incorrect => write!(f, "incorrect"),
+            Verdict::TooHigh => write!(f, "incorrect, too high"),
+            Verdict::TooLow => write!(f, "incorrect, too low"),
+            Verdict::Wait(seconds) => write!(f, "not checked, wait {seconds}s before retrying"),
+            Verdict::Unknown(text) => write!(f, "unexpected answer: {text}"),
+        }
+    }
+}
+
+/// The text of the `<article>` of a page, or of the whole page if it has none,
+/// without its tags.
+fn page_text(page: &str) -> String {
+    let article = match (page.find("<article"), page.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &page[start..end],
+        _ => page,
+    };
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Seconds the server asks to wait before answering again, if it does: what
+/// is left after answering too recently, or the lockout after a wrong answer
+/// (`please wait one minute`, `please wait 5 minutes`).
+pub fn wait_from_page(page: &str) -> Option<u64> {
+    let lower = page_text(page).to_ascii_lowercase();
+    if let Some(seconds) = wait_seconds(&lower) {
+        return Some(seconds);
+    }
+
+    let start = lower.find("please wait ")? + "please wait ".len();
+    let mut words = lower[start..].split_whitespace();
+    let minutes = match words.next()? {
+        "one" | "a" => 1,
+        n => n.parse().ok()?,
+    };
+    words
+        .next()
+        .is_some_and(|unit| unit.starts_with("minute"))
+        .then_some(minutes * 60)
+}
+
+/// Seconds in `you have 1m 23s left to wait`.
+fn wait_seconds(text: &str) -> Option<u64> {
+    let start = text.rfind("you have ")? + "you have ".len();
+    let end = start + text[start..].find(" left to wait")?;
+
+    text[start..end]
+        .split_whitespace()
+        .try_fold(0, |total, amount| {
+            let unit = amount.chars().last()?;
+            let n: u64 = amount[..amount.len() - 1].parse().ok()?;
+            let scale = match unit {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            Some(total + n * scale)
+        })
+}
+
+/// Seconds since the epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// A submission read back from the history.
+struct Attempt {
+    answer: String,
+    verdict: Verdict,
+    time: u64,
+    wait: u64,
+}
+
+/// Every answer ever submitted, so that known wrong answers aren't sent again:
+///
+/// ```toml
+/// [[day3.part1]]
+/// answer = "556057"
+/// verdict = "correct"
+/// time = 1701590400
+///
+/// [[day3.part2]]
+/// answer = "12"
+/// verdict = "too_low"
+/// time = 1701590460
+/// wait = 60
+/// ```
+///
+/// `wait` is how long the server asked to wait before the next answer, which
+/// is only there when it did.
+pub struct History {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl History {
+    /// Load the history at `path`. A missing file is an empty history.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let doc = load_doc(&path)?;
+        Ok(History { path, doc })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Answers submitted for a day and part that got a verdict, with when
+    /// they were and how long the server asked to wait after them.
+    fn attempts(&self, day: u32, part: u8) -> Vec<Attempt> {
+        let attempts = day_part(&self.doc, day, part).and_then(|part| part.as_array_of_tables());
+
+        attempts
+            .into_iter()
+            .flat_map(|attempts| attempts.iter())
+            .filter_map(|attempt| {
+                let number = |key| attempt.get(key).and_then(|v| v.as_integer());
+                let wait = number("wait").unwrap_or(0) as u64;
+                Some(Attempt {
+                    answer: attempt.get("answer")?.as_str()?.to_string(),
+                    verdict: Verdict::from_name(attempt.get("verdict")?.as_str()?, wait)?,
+                    time: number("time").unwrap_or(0) as u64,
+                    wait,
+                })
+            })
+            .collect()
+    }
+
+    /// Why submitting `answer` at `now` (in seconds since the epoch) would be
+    /// pointless, if it would: the answer is known to be wrong, or the server
+    /// is still making us wait.
+    pub fn rejects(&self, day: u32, part: u8, answer: &Answer, now: u64) -> Option<String> {
+        let answer = answer.to_string();
+        let value = answer.parse::<i64>().ok();
+        let attempts = self.attempts(day, part);
+
+        for Attempt {
+            answer: previous,
+            verdict,
+            ..
+        } in &attempts
+        {
+            let bound = previous.parse::<i64>().ok().zip(value);
+            match verdict {
+                // Not looked at, it can be sent again
+                Verdict::Wait(_) => {}
+                Verdict::Correct if *previous == answer => {
+                    return Some(format!("{answer} was already accepted"))
+                }
+                Verdict::Correct => return Some(format!("already solved with {previous}")),
+                _ if *previous == answer => {
+                    return Some(format!("{answer} was already submitted, it was {verdict}"))
+                }
+                Verdict::TooHigh if bound.is_some_and(|(high, value)| value >= high) => {
+                    return Some(format!(
+                        "{answer} is not below {previous}, which is too high"
+                    ))
+                }
+                Verdict::TooLow if bound.is_some_and(|(low, value)| value <= low) => {
+                    return Some(format!(
+                        "{answer} is not above {previous}, which is too low"
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        let until = attempts.iter().map(|a| a.time + a.wait).max()?;
+        (until > now).then(|| format!("the server asked to wait {0}s more", until - now))
+    }
+
+    /// Add an attempt made now, after which the server asked to `wait` that
+    /// many seconds, failing if the file has something else than a table for
+    /// the day or an array of tables for the part.
+    pub fn record(
+        &mut self,
+        day: u32,
+        part: u8,
+        answer: &Answer,
+        verdict: &Verdict,
+        wait: Option<u64>,
+    ) -> Result<()> {
+        let mut attempt = Table::new();
+        attempt.insert("answer", value(answer.to_string()));
+        attempt.insert("verdict", value(verdict.name()));
+        attempt.insert("time", value(now() as i64));
+        if let Some(seconds) = wait {
+            attempt.insert("wait", value(seconds as i64));
+        }
+
+        let attempts = Item::ArrayOfTables(ArrayOfTables::new());
+        day_part_entry(&mut self.doc, day, part, attempts)?
+            .as_array_of_tables_mut()
+            .ok_or_else(|| invalid_entry(day, part, "an array of tables"))?
+            .push(attempt);
+
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, self.doc.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{mock, Client};
+    use crate::submit::{now, wait_from_page, History, Verdict};
+    use utils::Answer;
+
+    const WRONG: &str = "<html><body><main><article><p>That's not the right answer; \
+        your answer is too high.  If you're stuck, make sure you're using the full input \
+        data. <a href=\"/2023/day/3\">[Return to Day 3]</a></p></article></main></body></html>";
+
+    #[test]
+    fn test_verdicts() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+
+        assert_eq!(
+            Verdict::Correct,
+            Verdict::from_page(&page(
+                "That's the right answer! You are one gold star closer."
+            ))
+        );
+        assert_eq!(Verdict::TooHigh, Verdict::from_page(WRONG));
+        assert_eq!(
+            Verdict::TooLow,
+            Verdict::from_page(&page(
+                "That's not the right answer; your answer is too low."
+            ))
+        );
+        assert_eq!(
+            Verdict::Incorrect,
+            Verdict::from_page(&page(
+                "That's not the right answer. Please wait one minute."
+            ))
+        );
+        assert_eq!(
+            Verdict::Wait(83),
+            Verdict::from_page(&page(
+                "You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again. You have 1m 23s left to wait."
+            ))
+        );
+        assert_eq!(
+            Verdict::Unknown("You don't seem to be solving the right level.".to_string()),
+            Verdict::from_page(&page(
+                "You don't seem to be <em>solving</em> the right level."
+            ))
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let path =
+            std::env::temp_dir().join(format!("aoc2023-history-{0}.toml", std::process::id()));
+        std::fs::write(&path, "# Attempts so far\n").unwrap();
+        let mut history = History::load(&path).unwrap();
+        assert_eq!(None, history.rejects(3, 1, &Answer::Int(10), now()));
+
+        history
+            .record(3, 1, &Answer::Int(100), &Verdict::TooHigh, None)
+            .unwrap();
+        history
+            .record(3, 1, &Answer::Int(10), &Verdict::TooLow, None)
+            .unwrap();
+        history
+            .record(3, 1, &Answer::Int(50), &Verdict::Wait(30), Some(30))
+            .unwrap();
+        history
+            .record(3, 1, &Answer::Int(42), &Verdict::Incorrect, None)
+            .unwrap();
+        history.save().unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("# Attempts so far\n"), "{saved}");
+        let history = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Once the wait is over
+        let later = now() + 60;
+        assert!(history.rejects(3, 1, &Answer::Int(100), later).is_some());
+        assert!(history.rejects(3, 1, &Answer::Int(150), later).is_some());
+        assert!(history.rejects(3, 1, &Answer::Int(3), later).is_some());
+        assert!(history.rejects(3, 1, &Answer::Int(42), later).is_some());
+        assert_eq!(None, history.rejects(3, 1, &Answer::Int(50), later));
+        assert_eq!(None, history.rejects(3, 2, &Answer::Int(100), later));
+        assert!(history.rejects(3, 1, &Answer::Int(50), now()).is_some());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, server) = mock::serve(vec![(200, WRONG)]);
+        let client = Client::new(url, "abc");
+
+        let page = client.submit(3, 2, "1234").unwrap();
+        assert_eq!(Verdict::TooHigh, Verdict::from_page(&page));
+
+        let requests = server.join().unwrap();
+        assert_eq!("POST", requests[0].method);
+        assert_eq!("/2023/day/3/answer", requests[0].path);
+        assert_eq!("level=2&answer=1234", requests[0].body);
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        const TOO_RECENT: &str = "<main><article><p>You gave an answer too recently; you \
+            have to wait after submitting an answer before trying again.  You have 45s left \
+            to wait. <a href=\"/2023/day/3\">[Return to Day 3]</a></p></article></main>";
+        const LOCKED_OUT: &str = "<main><article><p>That's not the right answer.  If you're \
+            stuck, make sure you're using the full input data.  Because you have guessed \
+            incorrectly 4 times on this puzzle, please wait 5 minutes before trying again. \
+            </p></article></main>";
+
+        let (url, server) = mock::serve(vec![(200, TOO_RECENT), (200, LOCKED_OUT)]);
+        let client = Client::new(url, "abc");
+        let path = std::env::temp_dir().join(format!("aoc2023-rate-{0}.toml", std::process::id()));
+        let mut history = History::load(&path).unwrap();
+
+        for (part, verdict, wait) in [(1, Verdict::Wait(45), 45), (2, Verdict::Incorrect, 300)] {
+            let page = client.submit(3, part, "1234").unwrap();
+            assert_eq!(verdict, Verdict::from_page(&page));
+            assert_eq!(Some(wait), wait_from_page(&page));
+            history
+                .record(3, part, &Answer::Int(1234), &verdict, Some(wait))
+                .unwrap();
+        }
+        history.save().unwrap();
+        let history = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(2, server.join().unwrap().len());
+
+        // Nothing is sent until the wait is over, then the answer that wasn't
+        // looked at can be sent again
+        let reason = history.rejects(3, 1, &Answer::Int(1234), now()).unwrap();
+        assert!(reason.starts_with("the server asked to wait"), "{reason}");
+        assert_eq!(None, history.rejects(3, 1, &Answer::Int(1234), now() + 46));
+
+        assert!(history
+            .rejects(3, 2, &Answer::Int(99), now() + 200)
+            .is_some());
+        assert_eq!(None, history.rejects(3, 2, &Answer::Int(99), now() + 301));
+    }
+}
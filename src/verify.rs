@@ -0,0 +1,156 @@
+use crate::registry::DAYS;
+use crate::runner::{map_jobs, print_table, silence_panics, try_run, Outcome};
+use crate::ProblemType;
+use utils::{base_kind, Answer, AnswerStore, Day, InputLayout, InputSource};
+
+enum Status {
+    Pass(Answer),
+    Fail { expected: Answer, got: Answer },
+    Missing,
+    NoInput,
+    ParseFailed(String),
+    Panicked(String),
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Pass(_) => "pass",
+            Status::Fail { .. } => "FAIL",
+            Status::Missing => "missing",
+            Status::NoInput => "no input",
+            Status::ParseFailed(_) => "PARSE",
+            Status::Panicked(_) => "PANIC",
+        }
+    }
+
+    fn details(&self) -> String {
+        match self {
+            Status::Pass(answer) => answer.to_string(),
+            Status::Fail { expected, got } => format!("expected {expected}, got {got}"),
+            Status::Missing => "no expected answer recorded".to_string(),
+            Status::ParseFailed(message) | Status::Panicked(message) => message.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+fn check(day: &Day, part: u8, source: InputSource, expected: Option<Answer>) -> Option<Status> {
+    let status = match (try_run(day, part, source), expected) {
+        (Outcome::MissingInput(_), None) => return None,
+        (Outcome::MissingInput(_), Some(_)) => Status::NoInput,
+        (Outcome::ParseFailed(e), _) => {
+            Status::ParseFailed(format!("line {0}, column {1}: {e}", e.line, e.column))
+        }
+        (Outcome::Panicked(message), _) => Status::Panicked(message),
+        (Outcome::Solved(_), None) => Status::Missing,
+        (Outcome::Solved(run), Some(expected)) if run.answer == expected => {
+            Status::Pass(run.answer)
+        }
+        (Outcome::Solved(run), Some(expected)) => Status::Fail {
+            expected,
+            got: run.answer,
+        },
+    };
+
+    Some(status)
+}
+
+/// Every kind of input a part has, on disk or in the store: `example`,
+/// `example1`, ..., `puzzle`.
+fn all_kinds(layout: &InputLayout, store: &AnswerStore, day: u32, part: u8) -> Vec<String> {
+    let mut kinds = vec![];
+    for type_ in ProblemType::ALL.iter() {
+        let mut of_type = layout.kinds(day, part, type_.name());
+        for kind in store.kinds(day, part) {
+            if base_kind(&kind) == type_.name() && !of_type.contains(&kind) {
+                of_type.push(kind);
+            }
+        }
+        of_type.sort_by_key(|kind| kind[type_.name().len()..].parse::<u32>().ok());
+        kinds.extend(of_type);
+    }
+    kinds
+}
+
+/// Check every day/part/input combination, possibly in parallel, and print a
+/// report of the results in order. Returns whether none of them failed.
+fn check_all(
+    layout: &InputLayout,
+    store: &AnswerStore,
+    jobs: &[(&Day, u8, String)],
+    sequential: bool,
+) -> bool {
+    let _quiet = silence_panics();
+
+    let statuses = map_jobs(jobs, sequential, |(day, part, kind)| {
+        let source = InputSource::File(layout.path(day.number, *part, kind));
+        check(day, *part, source, store.get(day.number, *part, kind))
+    });
+
+    let mut rows = vec![];
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for ((day, part, kind), status) in jobs.iter().zip(statuses) {
+        let Some(status) = status else {
+            continue;
+        };
+
+        match status {
+            Status::Pass(_) => passed += 1,
+            Status::Missing | Status::NoInput => missing += 1,
+            Status::Fail { .. } | Status::ParseFailed(_) | Status::Panicked(_) => failed += 1,
+        }
+
+        rows.push([
+            day.number.to_string(),
+            part.to_string(),
+            kind.to_string(),
+            status.name().to_string(),
+            status.details(),
+        ]);
+    }
+
+    print_table(&["Day", "Part", "Type", "Status", "Details"], &rows);
+    println!("\n{passed} passed, {failed} failed, {missing} missing");
+
+    failed == 0
+}
+
+/// Run the given days (or all of them) against the answer store and print a
+/// report. Returns whether no answer has changed.
+pub fn verify(
+    layout: &InputLayout,
+    store: &AnswerStore,
+    day: Option<&Day>,
+    sequential: bool,
+) -> bool {
+    let days = match day {
+        Some(day) => vec![day],
+        None => DAYS.iter().collect(),
+    };
+
+    let mut jobs = vec![];
+    for day in days {
+        for part in 1..=2 {
+            for kind in all_kinds(layout, store, day.number, part) {
+                jobs.push((day, part, kind));
+            }
+        }
+    }
+
+    check_all(layout, store, &jobs, sequential)
+}
+
+/// Run a part of a day on the given inputs and print which of them give the
+/// expected answer. Returns whether none of them failed.
+pub fn verify_part(
+    layout: &InputLayout,
+    store: &AnswerStore,
+    day: &Day,
+    part: u8,
+    kinds: &[String],
+    sequential: bool,
+) -> bool {
+    let jobs: Vec<_> = kinds.iter().map(|kind| (day, part, kind.clone())).collect();
+    check_all(layout, store, &jobs, sequential)
+}
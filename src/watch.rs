@@ -0,0 +1,102 @@
+use crate::runner::{print_run, silence_panics, try_solve, Outcome};
+use crate::ProblemType;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+use utils::{AnswerStore, Day, InputLayout};
+
+/// How often the watched files are looked at.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What is known of a file to tell whether it changed.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = path.metadata().ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Files worth watching when running `part` of `day` on `input`: the input
+/// itself, the other inputs of the part, and the expected answers.
+fn watched_files(
+    day: &Day,
+    part: u8,
+    input: &Path,
+    layout: &InputLayout,
+    answers: &Path,
+) -> Vec<PathBuf> {
+    let mut paths = vec![input.to_path_buf()];
+    for type_ in ProblemType::ALL.iter() {
+        for kind in layout.kinds(day.number, part, type_.name()) {
+            let path = layout.path(day.number, part, &kind);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.push(answers.to_path_buf());
+    paths
+}
+
+fn run_once(day: &Day, part: u8, input: &Path, kind: &str, answers: &Path) {
+    // Clear the screen and go back to the top
+    print!("\x1b[2J\x1b[H");
+    println!(
+        "{0} part {part} on {1}, watching for changes (Ctrl-C to stop)\n",
+        day.name,
+        input.display()
+    );
+
+    let content = match std::fs::read_to_string(input) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Could not read {0}: {e}", input.display());
+            return;
+        }
+    };
+
+    let outcome = {
+        let _quiet = silence_panics();
+        try_solve(day, part, &content)
+    };
+
+    match outcome {
+        Outcome::Solved(run) => {
+            print_run(&run);
+            let expected = AnswerStore::load(answers)
+                .ok()
+                .and_then(|store| store.get(day.number, part, kind));
+            match expected {
+                Some(expected) if expected == run.answer => println!("Matches {kind} answer"),
+                Some(expected) => println!("Expected {expected}"),
+                None => {}
+            }
+        }
+        Outcome::ParseFailed(e) => println!("{}", e.render(&input.display().to_string(), &content)),
+        Outcome::Panicked(message) => println!("Panicked: {message}"),
+        Outcome::MissingInput(source) => println!("Could not read {source}"),
+    }
+}
+
+/// Run `part` of `day` on `input` each time it, the other inputs of the day or
+/// the expected answers change, until interrupted.
+pub fn watch(
+    day: &Day,
+    part: u8,
+    input: &Path,
+    kind: &str,
+    layout: &InputLayout,
+    answers: &Path,
+) -> ! {
+    let paths = watched_files(day, part, input, layout, answers);
+    let mut last: Vec<Stamp> = vec![];
+
+    loop {
+        let stamps: Vec<Stamp> = paths.iter().map(|path| stamp(path)).collect();
+        if stamps != last {
+            run_once(day, part, input, kind, answers);
+            last = stamps;
+        }
+        sleep(POLL_INTERVAL);
+    }
+}
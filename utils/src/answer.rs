@@ -0,0 +1,79 @@
+use std::fmt::{Display, Formatter};
+
+/// The answer of a single part of a problem.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// Most puzzles boil down to a number
+    Int(i64),
+    /// A single line of text, like a password
+    Str(String),
+    /// Multiple lines of text, for when the answer is drawn on a grid
+    Text(String),
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Text(_))
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{i}"),
+            Answer::Str(s) => f.write_str(s),
+            Answer::Text(t) => f.write_str(t),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(
+                        i64::try_from(value)
+                            .unwrap_or_else(|_| panic!("Answer {value} does not fit in an i64")),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        if value.contains('\n') {
+            Answer::Text(value)
+        } else {
+            Answer::Str(value)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::from(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Answer;
+
+    #[test]
+    fn test_from_int() {
+        assert_eq!(Answer::Int(42), Answer::from(42_u32));
+        assert_eq!(Answer::Int(-3), Answer::from(-3_i32));
+        assert_eq!("1234", Answer::from(1234_u64).to_string());
+    }
+
+    #[test]
+    fn test_from_string() {
+        assert_eq!(Answer::Str("abc".to_string()), Answer::from("abc"));
+        assert!(Answer::from("#.\n.#").is_multiline());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::store::{day_part, day_part_entry, invalid_entry, load_doc};
+use crate::Answer;
+use std::io::Result;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, DocumentMut, Item, Table, TableLike};
+
+/// Default location of the answer store, relative to the working directory.
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+/// Known answers, keyed by day, part and input kind:
+///
+/// ```toml
+/// [day3.part1]
+/// example = 4361
+/// puzzle = 556057
+/// ```
+///
+/// The file is edited in place, so its comments and formatting are kept.
+#[derive(Debug, Clone)]
+pub struct AnswerStore {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl AnswerStore {
+    /// Load the store at `path`. A missing file is an empty store.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let doc = load_doc(&path)?;
+        Ok(AnswerStore { path, doc })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn entry(&self, day: u32, part: u8) -> Option<&dyn TableLike> {
+        day_part(&self.doc, day, part)?.as_table_like()
+    }
+
+    /// The expected answer for `kind` (`example`, `puzzle`, ...), if known.
+    pub fn get(&self, day: u32, part: u8, kind: &str) -> Option<Answer> {
+        let item = self.entry(day, part)?.get(kind)?;
+        match (item.as_integer(), item.as_str()) {
+            (Some(i), _) => Some(Answer::Int(i)),
+            (_, Some(s)) => Some(Answer::from(s)),
+            _ => None,
+        }
+    }
+
+    /// All the input kinds that have an answer for this day and part.
+    pub fn kinds(&self, day: u32, part: u8) -> Vec<String> {
+        self.entry(day, part)
+            .map(|entry| entry.iter().map(|(kind, _)| kind.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Record the answer for `kind`, failing if the file has something else
+    /// than a table where the day or the part should be.
+    pub fn set(&mut self, day: u32, part: u8, kind: &str, answer: &Answer) -> Result<()> {
+        let answer = match answer {
+            Answer::Int(i) => value(*i),
+            Answer::Str(s) | Answer::Text(s) => value(s.as_str()),
+        };
+
+        day_part_entry(&mut self.doc, day, part, Item::Table(Table::new()))?
+            .as_table_like_mut()
+            .ok_or_else(|| invalid_entry(day, part, "a table"))?
+            .insert(kind, answer);
+
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, self.doc.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Answer, AnswerStore};
+
+    #[test]
+    fn test_get_and_set() {
+        let path =
+            std::env::temp_dir().join(format!("aoc2023-get-and-set-{0}.toml", std::process::id()));
+        std::fs::write(&path, "[day3.part1]\nexample = 4361\n").unwrap();
+
+        let mut store = AnswerStore::load(&path).unwrap();
+        assert_eq!(Some(Answer::Int(4361)), store.get(3, 1, "example"));
+        assert_eq!(None, store.get(3, 1, "puzzle"));
+        assert_eq!(None, store.get(3, 2, "example"));
+
+        store.set(3, 2, "example", &Answer::from("abc")).unwrap();
+        store.save().unwrap();
+
+        let store = AnswerStore::load(&path).unwrap();
+        assert_eq!(Some(Answer::from("abc")), store.get(3, 2, "example"));
+        assert_eq!(vec!["example".to_string()], store.kinds(3, 1));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_keeps_formatting() {
+        let path = std::env::temp_dir().join(format!(
+            "aoc2023-keeps-formatting-{0}.toml",
+            std::process::id()
+        ));
+        let content =
+            "# Answers to the puzzles\n\n[day5.part1]\npuzzle   = 3  # the third\nexample = 35\n";
+        std::fs::write(&path, content).unwrap();
+
+        let mut store = AnswerStore::load(&path).unwrap();
+        store.set(5, 1, "example2", &Answer::Int(8)).unwrap();
+        store.set(6, 1, "example", &Answer::Int(288)).unwrap();
+        store.save().unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            format!("{content}example2 = 8\n\n[day6.part1]\nexample = 288\n"),
+            saved
+        );
+    }
+
+    #[test]
+    fn test_not_a_table() {
+        let path =
+            std::env::temp_dir().join(format!("aoc2023-not-a-table-{0}.toml", std::process::id()));
+        std::fs::write(&path, "day3 = 4361\n").unwrap();
+
+        let mut store = AnswerStore::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let e = store.set(3, 1, "example", &Answer::Int(4361)).unwrap_err();
+        assert_eq!("`day3` is not a table", e.to_string());
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let store = AnswerStore::load("this/file/does/not/exist.toml").unwrap();
+        assert_eq!(None, store.get(1, 1, "example"));
+    }
+}
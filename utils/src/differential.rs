@@ -0,0 +1,158 @@
+use std::fmt::Debug;
+use std::ops::Range;
+
+/// Small and deterministic random number generator (SplitMix64), to generate
+/// inputs that can be generated again from the same seed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "Empty range {range:?}");
+        // The modulo bias doesn't matter for generating test inputs
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// One of `items`, which must not be empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len() as u64) as usize]
+    }
+}
+
+/// A solver of the inputs of type `I`.
+type Solver<I, O> = Box<dyn Fn(&I) -> O>;
+
+/// Checks that a fast solver agrees with a reference one, usually naive but
+/// obviously right, on many generated inputs:
+///
+/// ```
+/// use utils::Differential;
+///
+/// Differential::new("sum", |rng| rng.range(0..1000))
+///     .reference(|n| (1..=*n).sum::<u64>())
+///     .fast(|n| n * (n + 1) / 2)
+///     .cases(500)
+///     .run();
+/// ```
+///
+/// Every case has its own seed, which a failure reports so that it can be
+/// run again alone with [`Differential::seed`] and `.cases(1)`.
+pub struct Differential<I, O> {
+    name: &'static str,
+    generate: Box<dyn Fn(&mut Rng) -> I>,
+    reference: Option<Solver<I, O>>,
+    fast: Option<Solver<I, O>>,
+    cases: u64,
+    seed: u64,
+}
+
+impl<I: Debug, O: Debug + PartialEq> Differential<I, O> {
+    pub fn new(name: &'static str, generate: impl Fn(&mut Rng) -> I + 'static) -> Self {
+        Differential {
+            name,
+            generate: Box::new(generate),
+            reference: None,
+            fast: None,
+            cases: 1000,
+            seed: 0,
+        }
+    }
+
+    /// The solver known to be right.
+    pub fn reference(mut self, solve: impl Fn(&I) -> O + 'static) -> Self {
+        self.reference = Some(Box::new(solve));
+        self
+    }
+
+    /// The solver to check against the reference.
+    pub fn fast(mut self, solve: impl Fn(&I) -> O + 'static) -> Self {
+        self.fast = Some(Box::new(solve));
+        self
+    }
+
+    /// Number of inputs to generate, 1000 unless set.
+    pub fn cases(mut self, cases: u64) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Seed of the first case, the next ones counting up from it.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Compare the solvers on every case, panicking at the first input they
+    /// don't agree on.
+    #[track_caller]
+    pub fn run(&self) {
+        let name = self.name;
+        let reference = self.reference.as_ref().expect("No reference solver");
+        let fast = self.fast.as_ref().expect("No fast solver");
+
+        for seed in self.seed..self.seed + self.cases {
+            let input = (self.generate)(&mut Rng::new(seed));
+            let expected = reference(&input);
+            let got = fast(&input);
+
+            if got != expected {
+                panic!(
+                    "{name}: the fast solver gave {got:?} instead of {expected:?} with seed {seed}, on {input:#?}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Differential, Rng};
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let numbers: Vec<_> = (0..100).map(|_| a.range(10..20)).collect();
+        assert_eq!(
+            numbers,
+            (0..100).map(|_| b.range(10..20)).collect::<Vec<_>>()
+        );
+        assert!(numbers.iter().all(|n| (10..20).contains(n)));
+        assert!(numbers.iter().any(|n| *n != numbers[0]));
+    }
+
+    #[test]
+    fn test_agreeing() {
+        Differential::new("double", |rng| rng.range(0..1 << 32))
+            .reference(|n| n + n)
+            .fast(|n| n << 1)
+            .run();
+    }
+
+    #[test]
+    #[should_panic(expected = "the fast solver gave 1 instead of 0 with seed")]
+    fn test_disagreeing() {
+        Differential::new("parity", |rng| rng.range(0..100))
+            .reference(|n| n % 2)
+            .fast(|_| 1)
+            .seed(42)
+            .run();
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter};
+
+/// Why an input could not be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the input the error is on
+    pub line: usize,
+    /// 1-based column, in characters, where the offending text starts
+    pub column: usize,
+    /// The text that could not be parsed
+    pub text: String,
+    /// What was expected instead, e.g. "a number"
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, expected: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Error on `text`, which must be a slice of `line`, the column being
+    /// worked out from where it sits in the line. The line number is 1 until
+    /// set with [`ParseError::on_line`], for parsers that only see one line.
+    pub fn in_line(line: &str, text: &str, expected: impl Into<String>) -> Self {
+        ParseError::new(1, column_of(line, text), text, expected)
+    }
+
+    /// Error about something missing at the end of the input.
+    pub fn at_end(input: &str, expected: impl Into<String>) -> Self {
+        let line = input.lines().count().max(1);
+        let column = input.lines().last().map_or(0, |l| l.chars().count()) + 1;
+        ParseError::new(line, column, "", expected)
+    }
+
+    /// Move an error found by a line parser to the line it really is on.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// Pretty print the error, pointing at the offending text in the input
+    /// that `source` (usually a file name) holds.
+    pub fn render(&self, source: &str, input: &str) -> String {
+        let line_text = input.lines().nth(self.line.wrapping_sub(1)).unwrap_or("");
+        let number = self.line.to_string();
+        let margin = " ".repeat(number.len());
+        let underline = "^".repeat(self.text.chars().count().max(1));
+        let padding = " ".repeat(self.column.saturating_sub(1));
+
+        format!(
+            "error: {self}\n{margin}--> {source}:{0}:{1}\n{margin} |\n{number} | {line_text}\n{margin} | {padding}{underline}",
+            self.line, self.column
+        )
+    }
+}
+
+/// 1-based column of `text` in `line`, if it is a slice of it, or 1 otherwise.
+fn column_of(line: &str, text: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (text.as_ptr() as usize).wrapping_sub(start);
+
+    if offset.saturating_add(text.len()) <= line.len() && line.is_char_boundary(offset) {
+        line[..offset].chars().count() + 1
+    } else {
+        1
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.text.is_empty() {
+            write!(f, "expected {0}", self.expected)
+        } else {
+            write!(f, "expected {0}, found `{1}`", self.expected, self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseError;
+
+    #[test]
+    fn test_in_line() {
+        let line = "Game 12: 3 blue, x red";
+        let e = ParseError::in_line(line, &line[17..18], "a number").on_line(4);
+
+        assert_eq!(4, e.line);
+        assert_eq!(18, e.column);
+        assert_eq!("expected a number, found `x`", e.to_string());
+    }
+
+    #[test]
+    fn test_not_a_slice() {
+        let e = ParseError::in_line("abc", "def", "something");
+        assert_eq!(1, e.column);
+    }
+
+    #[test]
+    fn test_at_end() {
+        let e = ParseError::at_end("a\nbcd\n", "more lines");
+        assert_eq!((2, 4), (e.line, e.column));
+        assert_eq!("expected more lines", e.to_string());
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "Time: 1 2\nDistance: 3 y\n";
+        let line = input.lines().nth(1).unwrap();
+        let e = ParseError::in_line(line, &line[12..], "a number").on_line(2);
+
+        let expected = "error: expected a number, found `y`
+ --> day6.example:2:13
+  |
+2 | Distance: 3 y
+  |             ^";
+        assert_eq!(expected, e.render("day6.example", input));
+    }
+}
@@ -0,0 +1,316 @@
+use crate::ParseError;
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// Offsets of the 4 neighbours of a cell, clockwise from the one above.
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Offsets of the 8 neighbours of a cell, clockwise from the top left one.
+const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// A rectangle of cells, addressed by `(row, col)` positions from the top left.
+///
+/// Accessing a position outside of it with [`Grid::get`] gives `None`, while
+/// indexing with `grid[(row, col)]` panics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self {
+        Grid {
+            rows,
+            cols,
+            cells: vec![value; rows * cols],
+        }
+    }
+}
+
+impl<T: Clone + Default> Grid<T> {
+    /// A grid with every cell set to the default value.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Grid::filled(rows, cols, T::default())
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid out of its cells, row after row, or `None` if there are not
+    /// `rows * cols` of them.
+    pub fn from_cells(rows: usize, cols: usize, cells: Vec<T>) -> Option<Self> {
+        (cells.len() == rows * cols).then_some(Grid { rows, cols, cells })
+    }
+
+    /// Parse a grid with one character per cell and one line per row, turning
+    /// every character into a cell with `cell`. A character it refuses is
+    /// reported as not being `expected`, as are lines of different lengths.
+    /// Blank lines at the end are ignored.
+    pub fn parse_with(
+        s: &str,
+        expected: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let s = s.trim_end_matches(['\n', '\r']);
+        let cols = s
+            .lines()
+            .next()
+            .filter(|line| !line.is_empty())
+            .ok_or_else(|| ParseError::at_end(s, "at least one line"))?
+            .chars()
+            .count();
+
+        let mut cells = Vec::with_capacity(s.len());
+        let mut rows = 0;
+        for (i, line) in s.lines().enumerate() {
+            let len = line.chars().count();
+            if len != cols {
+                let expected = format!("a line of {cols} characters, not {len}");
+                return Err(ParseError::in_line(line, line, expected).on_line(i + 1));
+            }
+
+            for (offset, c) in line.char_indices() {
+                let text = &line[offset..offset + c.len_utf8()];
+                let value = cell(c)
+                    .ok_or_else(|| ParseError::in_line(line, text, expected).on_line(i + 1))?;
+                cells.push(value);
+            }
+            rows += 1;
+        }
+
+        Ok(Grid { rows, cols, cells })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Number of rows and columns.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    fn offset(&self, (row, col): (usize, usize)) -> Option<usize> {
+        self.contains((row, col)).then(|| row * self.cols + col)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.offset((row, col)).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.offset((row, col)).map(|i| &mut self.cells[i])
+    }
+
+    /// Set a cell, returning whether it is in the grid.
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> bool {
+        match self.get_mut(row, col) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The position `delta` rows and columns away, if it is in the grid.
+    pub fn step(
+        &self,
+        (row, col): (usize, usize),
+        (d_row, d_col): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(d_row)?;
+        let col = col.checked_add_signed(d_col)?;
+        self.contains((row, col)).then_some((row, col))
+    }
+
+    /// Positions above, right, below and left of a cell that are in the grid.
+    pub fn neighbours4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |&delta| self.step(position, delta))
+    }
+
+    /// Positions around a cell, diagonals included, that are in the grid.
+    pub fn neighbours8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ALL_AROUND
+            .iter()
+            .filter_map(move |&delta| self.step(position, delta))
+    }
+
+    /// Every position, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    /// Every cell along with its position, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The cells of a row, which must be in the grid.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows, "row {row} out of a grid of {0}", self.rows);
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The cells of a column, from the top, which must be in the grid.
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            col < self.cols,
+            "column {col} out of a grid of {0}",
+            self.cols
+        );
+        self.cells.iter().skip(col).step_by(self.cols)
+    }
+
+    /// Every row, from the top.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        // A grid without columns still has rows, which chunks can't represent
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    /// A grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col)
+            .unwrap_or_else(|| panic!("({row}, {col}) out of a {0}x{1} grid", self.rows, self.cols))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (rows, cols) = self.dims();
+        self.get_mut(row, col)
+            .unwrap_or_else(|| panic!("({row}, {col}) out of a {rows}x{cols} grid"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, "a character", Some)
+    }
+}
+
+/// One line per row, with the cells next to each other.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.iter_rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_access() {
+        let mut grid: Grid<u8> = Grid::new(2, 3);
+        assert_eq!(Some(&0), grid.get(1, 2));
+        assert_eq!(None, grid.get(2, 0));
+        assert_eq!(None, grid.get(0, 3));
+
+        assert!(grid.set(1, 2, 7));
+        assert!(!grid.set(0, 3, 7));
+        grid[(0, 1)] = 4;
+        assert_eq!(7, grid[(1, 2)]);
+        assert_eq!(&[0, 4, 0], grid.row(0));
+        assert_eq!(vec![&0, &7], grid.col(2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "(0, 3) out of a 2x3 grid")]
+    fn test_index_out_of_grid() {
+        let grid: Grid<u8> = Grid::new(2, 3);
+        let _ = grid[(0, 3)];
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<u8> = Grid::new(3, 4);
+
+        let corner: Vec<_> = grid.neighbours8((0, 0)).collect();
+        assert_eq!(vec![(0, 1), (1, 1), (1, 0)], corner);
+
+        let edge: Vec<_> = grid.neighbours4((2, 3)).collect();
+        assert_eq!(vec![(1, 3), (2, 2)], edge);
+
+        assert_eq!(8, grid.neighbours8((1, 2)).count());
+        assert_eq!(None, grid.step((1, 3), (0, 1)));
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::from_str("ab.\n.c.\n\n").unwrap();
+        assert_eq!((2, 3), grid.dims());
+        assert_eq!('c', grid[(1, 1)]);
+        assert_eq!("ab.\n.c.\n", grid.to_string());
+
+        let positions: Vec<_> = grid.iter().filter(|(_, c)| **c != '.').collect();
+        assert_eq!(
+            vec![((0, 0), &'a'), ((0, 1), &'b'), ((1, 1), &'c')],
+            positions
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = Grid::from_str("...\n..\n...").unwrap_err();
+        assert_eq!((2, 1), (e.line, e.column));
+        assert_eq!("..", e.text);
+
+        let digit = |c: char| c.to_digit(10);
+        let e = Grid::parse_with("12\n3x", "a digit", digit).unwrap_err();
+        assert_eq!((2, 2), (e.line, e.column));
+        assert_eq!("expected a digit, found `x`", e.to_string());
+
+        assert!(Grid::from_str("").is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the directory the inputs are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Where the inputs live on disk: `<dir>/dayN.<kind>`, where kind is
+/// `example` or `puzzle`. A part can override the day-wide file with
+/// `<dir>/dayN.partP.<kind>`, for days where the parts have different examples.
+/// Days with several examples number them: `example1`, `example2`, ...
+#[derive(Debug, Clone)]
+pub struct InputLayout {
+    dir: PathBuf,
+}
+
+impl Default for InputLayout {
+    fn default() -> Self {
+        InputLayout::new("files")
+    }
+}
+
+impl InputLayout {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        InputLayout { dir: dir.into() }
+    }
+
+    /// The default layout, unless overridden by [`INPUT_DIR_VAR`].
+    pub fn from_env() -> Self {
+        match std::env::var_os(INPUT_DIR_VAR) {
+            Some(dir) => InputLayout::new(dir),
+            None => InputLayout::default(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File shared by both parts of a day.
+    pub fn day_path(&self, day: u32, kind: &str) -> PathBuf {
+        self.dir.join(format!("day{day}.{kind}"))
+    }
+
+    /// File for a single part of a day, falling back on the day-wide file
+    /// if the part has none of its own.
+    pub fn path(&self, day: u32, part: u8, kind: &str) -> PathBuf {
+        let part_path = self.dir.join(format!("day{day}.part{part}.{kind}"));
+        if part_path.exists() {
+            part_path
+        } else {
+            self.day_path(day, kind)
+        }
+    }
+
+    /// Every input of a part of the kind `kind`: `kind` itself and its numbered
+    /// variants, in order. Just `kind` if there are none on disk, so that its
+    /// absence can be reported.
+    pub fn kinds(&self, day: u32, part: u8, kind: &str) -> Vec<String> {
+        let prefixes = [
+            format!("day{day}.{kind}"),
+            format!("day{day}.part{part}.{kind}"),
+        ];
+        let entries = std::fs::read_dir(&self.dir).into_iter().flatten().flatten();
+
+        let mut numbers: Vec<Option<u32>> = entries
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let suffix = prefixes
+                    .iter()
+                    .find_map(|p| name.strip_prefix(p.as_str()))?;
+                match suffix {
+                    "" => Some(None),
+                    n if n.bytes().all(|b| b.is_ascii_digit()) => n.parse().ok().map(Some),
+                    _ => None,
+                }
+            })
+            .collect();
+        numbers.sort();
+        numbers.dedup();
+
+        if numbers.is_empty() {
+            return vec![kind.to_string()];
+        }
+        numbers
+            .into_iter()
+            .map(|n| match n {
+                None => kind.to_string(),
+                Some(n) => format!("{kind}{n}"),
+            })
+            .collect()
+    }
+}
+
+/// Kind of input without its number, `example` for `example2`.
+pub fn base_kind(kind: &str) -> &str {
+    kind.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+/// Where to read an input from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// `-` means stdin, like most command line tools.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn read(&self) -> std::io::Result<String> {
+        match self {
+            InputSource::File(path) => std::fs::read_to_string(path),
+            InputSource::Stdin => {
+                let mut buff = String::new();
+                std::io::stdin().read_to_string(&mut buff)?;
+                Ok(buff)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => f.write_str("<stdin>"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InputLayout, InputSource};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_day_path() {
+        let layout = InputLayout::new("inputs");
+        assert_eq!(
+            PathBuf::from("inputs/day3.puzzle"),
+            layout.day_path(3, "puzzle")
+        );
+        // No part specific file there, so it falls back on the day
+        assert_eq!(
+            PathBuf::from("inputs/day3.example"),
+            layout.path(3, 2, "example")
+        );
+    }
+
+    #[test]
+    fn test_kinds() {
+        let dir = std::env::temp_dir().join(format!("aoc2023-kinds-{0}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "day1.example",
+            "day1.example2",
+            "day1.part2.example10",
+            "day1.puzzle",
+        ] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        let layout = InputLayout::new(&dir);
+
+        assert_eq!(vec!["example", "example2"], layout.kinds(1, 1, "example"));
+        assert_eq!(
+            vec!["example", "example2", "example10"],
+            layout.kinds(1, 2, "example")
+        );
+        assert_eq!(vec!["puzzle"], layout.kinds(1, 1, "puzzle"));
+        assert_eq!(vec!["example"], layout.kinds(2, 1, "example"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_source_from_arg() {
+        assert_eq!(InputSource::Stdin, InputSource::from_arg("-"));
+        assert_eq!(
+            InputSource::File(PathBuf::from("a.txt")),
+            InputSource::from_arg("a.txt")
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Range, Sub};
+
+/// What intervals can be made of: integers, mostly.
+pub trait Endpoint: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>> Endpoint for T {}
+
+/// The values from `start` included to `end` excluded, which is empty when
+/// they are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Endpoint> Interval<T> {
+    /// The interval from `start` to `end`, empty if `end` is before `start`.
+    pub fn new(start: T, end: T) -> Self {
+        Interval {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// The `len` values from `start`.
+    pub fn from_len(start: T, len: T) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether there is a value in both.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Values in both, if there are any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let both = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!both.is_empty()).then_some(both)
+    }
+
+    /// Values before `at`, and from `at` on, if there are any.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let before = Interval::new(self.start, self.end.min(at));
+        let after = Interval::new(self.start.max(at), self.end);
+        (
+            (!before.is_empty()).then_some(before),
+            (!after.is_empty()).then_some(after),
+        )
+    }
+
+    /// Values that are not in `other`: those before it and those after it.
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        let (before, _) = self.split_at(other.start);
+        let (_, after) = self.split_at(other.end);
+        (before, after)
+    }
+}
+
+impl<T: Endpoint> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Interval::new(range.start, range.end)
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{0}, {1})", self.start, self.end)
+    }
+}
+
+/// Values made of any number of intervals, kept sorted, apart and non-empty,
+/// so that touching intervals are merged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Endpoint> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    /// Add the values of `interval`, merging it with the ones it overlaps or
+    /// touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // Intervals entirely before and after the new one stay as they are
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+
+        let mut merged = interval;
+        if let (Some(left), Some(right)) = (self.intervals.get(first), last.checked_sub(1)) {
+            if first <= right {
+                merged.start = merged.start.min(left.start);
+                merged.end = merged.end.max(self.intervals[right].end);
+            }
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// The intervals, sorted.
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn count(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::default(), |count, interval| count + interval.len())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(i).is_some_and(|i| i.contains(value))
+    }
+
+    /// Smallest value.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    /// Values in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for interval in other.iter() {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    /// Values in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut both = IntervalSet::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            if let Some(common) = a.intersection(b) {
+                both.intervals.push(common);
+            }
+            // Move past whichever ends first, it can't overlap anything else
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        both
+    }
+
+    /// Values in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut left = IntervalSet::new();
+
+        for interval in self.iter() {
+            let mut rest = Some(*interval);
+            for removed in other.iter().filter(|removed| removed.overlaps(interval)) {
+                let Some(current) = rest else {
+                    break;
+                };
+                let (before, after) = current.difference(removed);
+                left.intervals.extend(before);
+                rest = after;
+            }
+            left.intervals.extend(rest);
+        }
+
+        left
+    }
+
+    /// Values before `at`, and from `at` on.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (mut before, mut after) = (IntervalSet::new(), IntervalSet::new());
+        for interval in self.iter() {
+            let (below, above) = interval.split_at(at);
+            before.intervals.extend(below);
+            after.intervals.extend(above);
+        }
+        (before, after)
+    }
+}
+
+impl<T: Endpoint> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// Moves values by pieces: every piece moves an interval so that it starts
+/// somewhere else, and values outside of all the pieces stay where they are.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OffsetMap<T> {
+    /// Sources and where they start once moved, sorted by source
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Endpoint> OffsetMap<T> {
+    pub fn new() -> Self {
+        OffsetMap { pieces: vec![] }
+    }
+
+    /// Move the values of `source` to start at `destination`. Pieces can't
+    /// overlap, the piece that is in the way is handed back if they do. An
+    /// empty `source` moves nothing, so it isn't kept.
+    pub fn insert(&mut self, source: Interval<T>, destination: T) -> Result<(), Interval<T>> {
+        if source.is_empty() {
+            return Ok(());
+        }
+
+        let i = self.pieces.partition_point(|(s, _)| s.end <= source.start);
+        match self.pieces.get(i) {
+            Some((other, _)) if other.overlaps(&source) => Err(*other),
+            _ => {
+                self.pieces.insert(i, (source, destination));
+                Ok(())
+            }
+        }
+    }
+
+    /// Where a single value goes.
+    pub fn get(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|(s, _)| s.end <= value);
+        match self.pieces.get(i) {
+            Some((source, destination)) if source.contains(value) => {
+                *destination + (value - source.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// Where all the values of an interval go, in one go.
+    pub fn map_interval(&self, interval: Interval<T>, into: &mut IntervalSet<T>) {
+        let mut cursor = interval.start;
+        let first = self
+            .pieces
+            .partition_point(|(s, _)| s.end <= interval.start);
+
+        for (source, destination) in &self.pieces[first..] {
+            let Some(moved) = source.intersection(&interval) else {
+                break;
+            };
+            // Gap before the piece, which doesn't move
+            into.insert(Interval::new(cursor, moved.start));
+
+            let start = *destination + (moved.start - source.start);
+            into.insert(Interval::from_len(start, moved.len()));
+            cursor = moved.end;
+        }
+
+        into.insert(Interval::new(cursor, interval.end));
+    }
+
+    /// Where all the values of a set go.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut moved = IntervalSet::new();
+        for interval in set.iter() {
+            self.map_interval(*interval, &mut moved);
+        }
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Interval, IntervalSet, OffsetMap};
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(s, e)| Interval::new(s, e)).collect()
+    }
+
+    fn ranges(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter().map(|i| (i.start, i.end)).collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::from(2..8);
+        assert_eq!(6, a.len());
+        assert!(a.contains(2) && !a.contains(8));
+        assert!(Interval::new(5, 3).is_empty());
+
+        assert_eq!(
+            Some(Interval::from(5..8)),
+            a.intersection(&Interval::from(5..10))
+        );
+        assert_eq!(None, a.intersection(&Interval::from(8..10)));
+        assert_eq!(
+            (Some(Interval::from(2..4)), Some(Interval::from(4..8))),
+            a.split_at(4)
+        );
+        assert_eq!((None, Some(a)), a.split_at(0));
+        assert_eq!(
+            (Some(Interval::from(2..3)), Some(Interval::from(5..8))),
+            a.difference(&Interval::from(3..5))
+        );
+        assert_eq!("[2, 8)", a.to_string());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut s = set(&[(10, 12), (0, 2), (5, 6)]);
+        assert_eq!(vec![(0, 2), (5, 6), (10, 12)], ranges(&s));
+
+        // Touching intervals are merged
+        s.insert(Interval::from(2..3));
+        s.insert(Interval::from(4..5));
+        assert_eq!(vec![(0, 3), (4, 6), (10, 12)], ranges(&s));
+
+        s.insert(Interval::from(1..11));
+        assert_eq!(vec![(0, 12)], ranges(&s));
+        assert_eq!(12, s.count());
+        assert!(s.contains(11) && !s.contains(12));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 5), (10, 15)]);
+        let b = set(&[(3, 12), (14, 20)]);
+
+        assert_eq!(vec![(0, 20)], ranges(&a.union(&b)));
+        assert_eq!(
+            vec![(3, 5), (10, 12), (14, 15)],
+            ranges(&a.intersection(&b))
+        );
+        assert_eq!(vec![(0, 3), (12, 14)], ranges(&a.difference(&b)));
+        assert_eq!(vec![(5, 10), (15, 20)], ranges(&b.difference(&a)));
+
+        let (before, after) = a.split_at(12);
+        assert_eq!(vec![(0, 5), (10, 12)], ranges(&before));
+        assert_eq!(vec![(12, 15)], ranges(&after));
+    }
+
+    #[test]
+    fn test_offset_map() {
+        // Day 5's example seed-to-soil map
+        let mut map = OffsetMap::new();
+        map.insert(Interval::from_len(98, 2), 50).unwrap();
+        map.insert(Interval::from_len(50, 48), 52).unwrap();
+        assert_eq!(
+            Err(Interval::from(50..98)),
+            map.insert(Interval::from(60..61), 0)
+        );
+
+        assert_eq!(81, map.get(79));
+        assert_eq!(50, map.get(98));
+        assert_eq!(10, map.get(10));
+        assert_eq!(100, map.get(100));
+
+        let moved = map.map_set(&set(&[(45, 52), (97, 102)]));
+        // 45..50 stay, 50..52 go to 52..54, 97 to 99, 98..100 to 50..52
+        assert_eq!(vec![(45, 54), (99, 102)], ranges(&moved));
+    }
+
+    #[test]
+    fn test_empty_piece() {
+        let mut map = OffsetMap::new();
+        map.insert(Interval::from(0..10), 100).unwrap();
+        map.insert(Interval::from_len(15, 0), 500).unwrap();
+        map.insert(Interval::from(20..30), 200).unwrap();
+
+        assert_eq!(15, map.get(15));
+        let moved = map.map_set(&set(&[(5, 25)]));
+        // The empty piece at 15 doesn't stop 20..25 from moving
+        assert_eq!(vec![(10, 20), (105, 110), (200, 205)], ranges(&moved));
+    }
+}
@@ -0,0 +1,30 @@
+/// `s` as a JSON string, quoted and escaped.
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_string;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(r#""plain""#, json_string("plain"));
+        assert_eq!(r#""a \"b\" \\ c""#, json_string(r#"a "b" \ c"#));
+        assert_eq!(r#""1\n2\t3\u0001""#, json_string("1\n2\t3\u{1}"));
+    }
+}
@@ -2,27 +2,66 @@ use std::fs::File;
 use std::io::{Read, Result};
 use std::path::Path;
 
-pub trait Problem {
-    fn get_part1(&self) -> fn(bool) -> ();
-    fn get_part2(&self) -> fn(bool) -> ();
-}
+mod answer;
+mod answers;
+mod differential;
+mod error;
+mod grid;
+mod input;
+mod interval;
+mod json;
+mod lines;
+mod log;
+mod problem;
+mod registry;
+pub mod scan;
+mod sections;
+pub mod store;
+mod trace;
+
+pub use answer::Answer;
+pub use answers::{AnswerStore, DEFAULT_ANSWERS_FILE};
+pub use differential::{Differential, Rng};
+pub use error::ParseError;
+pub use grid::Grid;
+pub use input::{base_kind, InputLayout, InputSource, INPUT_DIR_VAR};
+pub use interval::{Endpoint, Interval, IntervalSet, OffsetMap};
+pub use json::json_string;
+pub use lines::{LineReader, Trim};
+pub use log::{log, log_enabled, set_log_level, Level};
+pub use problem::{Problem, Run, Solution};
+pub use registry::{find_day, Day};
+pub use sections::{Section, Sections};
+pub use trace::{chrome_trace, finish_trace, span, span_with, start_trace, Span, TraceEvent};
 
-/// Read a file line by line, clearing the empty ones.
+/// Read a file line by line, trimming them and clearing the empty ones. See
+/// [`LineReader`] to keep them as they are.
 pub fn lines_of_file(path: &str) -> Result<Vec<String>> {
-    let file = File::open(Path::new(path));
-    let mut buff = String::new();
-    file?
-        .read_to_string(&mut buff)
-        .map_err(|_| std::io::ErrorKind::InvalidData)?;
+    LineReader::new()
+        .trim(Trim::Both)
+        .keep_empty(false)
+        .read_file(path)
+}
 
-    let ret: Vec<String> = buff
-        .lines()
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
+/// Split a string line by line, trimming them and clearing the empty ones.
+pub fn lines_of_str(s: &str) -> Vec<String> {
+    LineReader::new()
+        .trim(Trim::Both)
+        .keep_empty(false)
+        .lines(s)
+        .into_iter()
         .map(|s| s.to_string())
-        .collect();
+        .collect()
+}
 
-    Ok(ret)
+/// Non-empty lines of a string along with their 1-based line number, for
+/// parsers that want to report where an error is. Trailing whitespace is
+/// removed, but not leading whitespace, so that the columns stay right.
+pub fn numbered_lines(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    LineReader::new()
+        .trim(Trim::End)
+        .keep_empty(false)
+        .numbered(s)
 }
 
 /// Content of str
@@ -32,10 +71,3 @@ pub fn str_of_file(path: &str) -> Result<String> {
     file?.read_to_string(&mut buff)?;
     Ok(buff)
 }
-
-pub fn filename(day: u32, debug: bool) -> String {
-    match debug {
-        false => format!("files/day{day}.puzzle"),
-        true => format!("files/day{day}.example"),
-    }
-}
@@ -0,0 +1,149 @@
+use crate::str_of_file;
+use std::io::Result;
+
+/// Which whitespace is removed from the lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trim {
+    No,
+    /// Only at the end, which keeps the columns right
+    End,
+    Both,
+}
+
+/// How an input is split into lines. By default, it is like [`str::lines`]:
+/// lines are kept as they are, empty ones included, the `\r` of `\r\n` line
+/// endings is removed and a newline at the very end doesn't add an empty line.
+///
+/// ```
+/// use utils::{LineReader, Trim};
+///
+/// let reader = LineReader::new().trim(Trim::Both).keep_empty(false);
+/// assert_eq!(vec!["a", "b"], reader.lines(" a\r\n\n b \n"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineReader {
+    trim: Trim,
+    keep_empty: bool,
+    crlf: bool,
+    strip_trailing_newline: bool,
+}
+
+impl Default for LineReader {
+    fn default() -> Self {
+        LineReader {
+            trim: Trim::No,
+            keep_empty: true,
+            crlf: true,
+            strip_trailing_newline: true,
+        }
+    }
+}
+
+impl LineReader {
+    pub fn new() -> Self {
+        LineReader::default()
+    }
+
+    /// Remove whitespace around the lines.
+    pub fn trim(mut self, trim: Trim) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Keep the lines that are empty, once trimmed. Blank lines often separate
+    /// sections of an input.
+    pub fn keep_empty(mut self, keep: bool) -> Self {
+        self.keep_empty = keep;
+        self
+    }
+
+    /// Remove the `\r` of `\r\n` line endings.
+    pub fn crlf(mut self, normalise: bool) -> Self {
+        self.crlf = normalise;
+        self
+    }
+
+    /// Drop the empty line after a newline at the very end of the input, and
+    /// only that one.
+    pub fn strip_trailing_newline(mut self, strip: bool) -> Self {
+        self.strip_trailing_newline = strip;
+        self
+    }
+
+    /// The lines of `s` along with their 1-based line number, which still
+    /// counts the lines that are left out.
+    pub fn numbered<'a>(&self, s: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+        let reader = *self;
+        let body = match reader.strip_trailing_newline {
+            true => s.strip_suffix('\n').unwrap_or(s),
+            false => s,
+        };
+
+        // An empty input has no lines, rather than a single empty one
+        (!s.is_empty())
+            .then(|| body.split('\n'))
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(move |(i, line)| (i + 1, reader.clean(line)))
+            .filter(move |(_, line)| reader.keep_empty || !line.is_empty())
+    }
+
+    fn clean<'a>(&self, line: &'a str) -> &'a str {
+        let line = match self.crlf {
+            true => line.strip_suffix('\r').unwrap_or(line),
+            false => line,
+        };
+
+        match self.trim {
+            Trim::No => line,
+            Trim::End => line.trim_end(),
+            Trim::Both => line.trim(),
+        }
+    }
+
+    /// The lines of `s`.
+    pub fn lines<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        self.numbered(s).map(|(_, line)| line).collect()
+    }
+
+    /// The lines of a file.
+    pub fn read_file(&self, path: &str) -> Result<Vec<String>> {
+        let content = str_of_file(path)?;
+        Ok(self.lines(&content).into_iter().map(String::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LineReader, Trim};
+
+    const INPUT: &str = "  a b \r\n\n\tc\r\n";
+
+    #[test]
+    fn test_default() {
+        let reader = LineReader::new();
+        assert_eq!(vec!["  a b ", "", "\tc"], reader.lines(INPUT));
+        assert_eq!(INPUT.lines().collect::<Vec<_>>(), reader.lines(INPUT));
+
+        assert_eq!(Vec::<&str>::new(), reader.lines(""));
+        assert_eq!(vec![""], reader.lines("\n"));
+        assert_eq!(vec!["a", ""], reader.lines("a\n\n"));
+    }
+
+    #[test]
+    fn test_options() {
+        let trimmed = LineReader::new().trim(Trim::Both);
+        assert_eq!(vec!["a b", "", "c"], trimmed.lines(INPUT));
+
+        let numbered: Vec<_> = LineReader::new()
+            .trim(Trim::End)
+            .keep_empty(false)
+            .numbered(INPUT)
+            .collect();
+        assert_eq!(vec![(1, "  a b"), (3, "\tc")], numbered);
+
+        let raw = LineReader::new().crlf(false).strip_trailing_newline(false);
+        assert_eq!(vec!["  a b \r", "", "\tc\r", ""], raw.lines(INPUT));
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt::{Arguments, Display, Formatter};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much a message matters. Only the messages at or above the current
+/// level, [`Level::Warn`] unless set otherwise, are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+    Trace = 4,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    /// The level `n` steps more verbose than this one (or less if negative),
+    /// staying within the known levels.
+    pub fn shifted(self, n: i32) -> Level {
+        let i = (self as i32 + n).clamp(0, Level::Trace as i32);
+        Level::ALL[i as usize]
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        })
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+pub fn set_log_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are printed, to skip building expensive ones.
+pub fn log_enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Print a message to stderr, if its level is enabled. Use the [`error!`],
+/// [`warn!`], [`info!`], [`debug!`] and [`trace!`] macros rather than this:
+/// they don't even evaluate their arguments when the level is disabled.
+///
+/// [`error!`]: crate::error!
+/// [`warn!`]: crate::warn!
+/// [`info!`]: crate::info!
+/// [`debug!`]: crate::debug!
+/// [`trace!`]: crate::trace!
+pub fn log(level: Level, module: &str, message: Arguments) {
+    if log_enabled(level) {
+        eprintln!("[{level} {module}] {message}");
+    }
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => {
+        if $crate::log_enabled($crate::Level::Error) {
+            $crate::log($crate::Level::Error, module_path!(), format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if $crate::log_enabled($crate::Level::Warn) {
+            $crate::log($crate::Level::Warn, module_path!(), format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log_enabled($crate::Level::Info) {
+            $crate::log($crate::Level::Info, module_path!(), format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log_enabled($crate::Level::Debug) {
+            $crate::log($crate::Level::Debug, module_path!(), format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log_enabled($crate::Level::Trace) {
+            $crate::log($crate::Level::Trace, module_path!(), format_args!($($arg)*))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Level;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_shifted() {
+        assert_eq!(Level::Debug, Level::Warn.shifted(2));
+        assert_eq!(Level::Trace, Level::Warn.shifted(10));
+        assert_eq!(Level::Error, Level::Warn.shifted(-1));
+        assert_eq!(Level::Error, Level::Warn.shifted(-5));
+    }
+
+    #[test]
+    fn test_disabled_arguments_not_evaluated() {
+        // Nothing changes the level from the default in these tests
+        let evaluated = Cell::new(0);
+        let count = || {
+            evaluated.set(evaluated.get() + 1);
+            evaluated.get()
+        };
+
+        crate::debug!("{0}", count());
+        crate::trace!("{0}", count());
+        assert_eq!(0, evaluated.get());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{span, Answer, ParseError};
+use std::time::{Duration, Instant};
+
+/// A day of the calendar. The input is parsed once into `Parsed`, which both
+/// parts then borrow.
+pub trait Problem {
+    type Parsed;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError>;
+    fn part1(&self, parsed: &Self::Parsed) -> Answer;
+    fn part2(&self, parsed: &Self::Parsed) -> Answer;
+}
+
+/// Outcome of running one part of a problem on an input.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Object-safe face of a [`Problem`], so the runner can hold any day without
+/// knowing its parsed type.
+pub trait Solution {
+    fn run(&self, part: u8, input: &str) -> Result<Run, ParseError>;
+}
+
+impl<P: Problem> Solution for P {
+    fn run(&self, part: u8, input: &str) -> Result<Run, ParseError> {
+        let start = Instant::now();
+        let parsed = {
+            let _s = span("parse");
+            self.parse(input)?
+        };
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answer = match part {
+            1 => {
+                let _s = span("part1");
+                self.part1(&parsed)
+            }
+            2 => {
+                let _s = span("part2");
+                self.part2(&parsed)
+            }
+            _ => panic!("Invalid problem part: {0}", part),
+        };
+        let solve_time = start.elapsed();
+
+        Ok(Run {
+            answer,
+            parse_time,
+            solve_time,
+        })
+    }
+}
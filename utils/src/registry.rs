@@ -0,0 +1,78 @@
+use crate::{ParseError, Run, Solution};
+
+/// A day of the calendar, declared by its crate with [`register_day!`] and
+/// picked up by the runner at build time.
+pub struct Day {
+    pub number: u32,
+    pub name: &'static str,
+    pub solution: &'static (dyn Solution + Sync),
+}
+
+impl Day {
+    pub fn run(&self, part: u8, input: &str) -> Result<Run, ParseError> {
+        self.solution.run(part, input)
+    }
+}
+
+/// Find a day by its name (`day3`) or number (`3`).
+pub fn find_day<'a>(days: &'a [Day], s: &str) -> Option<&'a Day> {
+    let s = s.to_ascii_lowercase();
+    let number = s.strip_prefix("day").unwrap_or(&s);
+    days.iter().find(|day| day.number.to_string() == number)
+}
+
+/// Declare the [`Problem`](crate::Problem) of a day crate. This must be
+/// called once at the root of the crate, which is then found by the runner
+/// as long as it is one of its dependencies.
+///
+/// ```ignore
+/// utils::register_day!(3, Day3());
+/// ```
+#[macro_export]
+macro_rules! register_day {
+    ($number:literal, $problem:expr) => {
+        pub const DAY: $crate::Day = $crate::Day {
+            number: $number,
+            name: concat!("day", $number),
+            solution: &$problem,
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{find_day, Answer, Day, ParseError, Problem};
+
+    struct Dummy();
+
+    impl Problem for Dummy {
+        type Parsed = ();
+
+        fn parse(&self, _input: &str) -> Result<Self::Parsed, ParseError> {
+            Ok(())
+        }
+
+        fn part1(&self, _parsed: &Self::Parsed) -> Answer {
+            Answer::Int(1)
+        }
+
+        fn part2(&self, _parsed: &Self::Parsed) -> Answer {
+            Answer::Int(2)
+        }
+    }
+
+    mod day12 {
+        crate::register_day!(12, super::Dummy());
+    }
+
+    #[test]
+    fn test_register_and_find() {
+        let days: [Day; 1] = [day12::DAY];
+
+        assert_eq!("day12", days[0].name);
+        assert_eq!(Answer::Int(2), days[0].run(2, "").unwrap().answer);
+        assert_eq!(12, find_day(&days, "Day12").unwrap().number);
+        assert_eq!(12, find_day(&days, "12").unwrap().number);
+        assert!(find_day(&days, "day1").is_none());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::ParseError;
+use std::fmt::{Display, Formatter};
+
+/// An integer type that numbers can be scanned as.
+pub trait Integer: Copy {
+    /// Name of the type, for errors
+    const NAME: &'static str;
+    /// Whether a `-` right before the digits makes the number negative
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    /// `self * 10 + digit`, or minus the digit when building a negative
+    /// number, or `None` on overflow.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($type:ty: $signed:expr),*) => {
+        $(impl Integer for $type {
+            const NAME: &'static str = stringify!($type);
+            const SIGNED: bool = $signed;
+            const ZERO: Self = 0;
+
+            fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                let shifted = self.checked_mul(10)?;
+                let digit = digit as Self;
+                match negative {
+                    true => shifted.checked_sub(digit),
+                    false => shifted.checked_add(digit),
+                }
+            }
+        })*
+    };
+}
+
+integer!(u8: false, u16: false, u32: false, u64: false, u128: false, usize: false);
+integer!(i8: true, i16: true, i32: true, i64: true, i128: true, isize: true);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanErrorKind {
+    /// The text is not made of digits, after an optional sign
+    NotAnInteger,
+    /// The number is too large (or too small) for the type it is read as
+    Overflow { type_name: &'static str },
+}
+
+/// A number that could not be scanned, with the text of it, which is a slice
+/// of the scanned input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanError<'a> {
+    pub text: &'a str,
+    pub kind: ScanErrorKind,
+}
+
+impl ScanError<'_> {
+    /// The error as a [`ParseError`] on `line`, which holds the scanned text.
+    pub fn in_line(&self, line: &str) -> ParseError {
+        let expected = match self.kind {
+            ScanErrorKind::NotAnInteger => "a number".to_string(),
+            ScanErrorKind::Overflow { type_name } => format!("a number that fits in {type_name}"),
+        };
+        ParseError::in_line(line, self.text, expected)
+    }
+}
+
+impl Display for ScanError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ScanErrorKind::NotAnInteger => write!(f, "`{0}` is not a number", self.text),
+            ScanErrorKind::Overflow { type_name } => {
+                write!(f, "`{0}` does not fit in {type_name}", self.text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScanError<'_> {}
+
+/// Build a number out of ASCII digits, which `text` is the source of.
+fn from_digits<'a, T: Integer>(
+    digits: &[u8],
+    negative: bool,
+    text: &'a str,
+) -> Result<T, ScanError<'a>> {
+    digits
+        .iter()
+        .try_fold(T::ZERO, |n, digit| n.push_digit(digit - b'0', negative))
+        .ok_or(ScanError {
+            text,
+            kind: ScanErrorKind::Overflow { type_name: T::NAME },
+        })
+}
+
+/// Every integer in some bytes, anything else between them being skipped. A
+/// `-` right before the digits makes a number negative when `T` is signed,
+/// and is skipped otherwise.
+pub fn ints_in_bytes<T: Integer>(bytes: &[u8]) -> Result<Vec<T>, ScanError<'_>> {
+    let mut numbers = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let negative = T::SIGNED && i > 0 && bytes[i - 1] == b'-';
+        let start = i - negative as usize;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        // Only ASCII from `start`, so this can't fail
+        let text = std::str::from_utf8(&bytes[start..i]).unwrap();
+        numbers.push(from_digits(
+            &bytes[start + negative as usize..i],
+            negative,
+            text,
+        )?);
+    }
+
+    Ok(numbers)
+}
+
+/// Every integer in a string, anything else between them being skipped, as
+/// with [`ints_in_bytes`]:
+///
+/// ```
+/// let numbers: Vec<i32> = utils::scan::ints("x=-3, y=14").unwrap();
+/// assert_eq!(vec![-3, 14], numbers);
+/// ```
+pub fn ints<T: Integer>(s: &str) -> Result<Vec<T>, ScanError<'_>> {
+    ints_in_bytes(s.as_bytes()).map_err(|e| {
+        // Give back a slice of `s` rather than of the bytes, for its column
+        let start = e.text.as_ptr() as usize - s.as_ptr() as usize;
+        ScanError {
+            text: &s[start..start + e.text.len()],
+            kind: e.kind,
+        }
+    })
+}
+
+/// A single integer, which the whole of `token` must be, with an optional
+/// sign (only `+` for unsigned types).
+pub fn int<T: Integer>(token: &str) -> Result<T, ScanError<'_>> {
+    let not_an_integer = ScanError {
+        text: token,
+        kind: ScanErrorKind::NotAnInteger,
+    };
+
+    let (negative, digits) = match token.as_bytes() {
+        [b'-', digits @ ..] if T::SIGNED => (true, digits),
+        [b'+', digits @ ..] => (false, digits),
+        digits => (false, digits),
+    };
+
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(not_an_integer);
+    }
+    from_digits(digits, negative, token)
+}
+
+/// The integers of a string made of integers separated by whitespace, every
+/// token having to be one, as with [`int`].
+pub fn whitespace_ints<T: Integer>(s: &str) -> Result<Vec<T>, ScanError<'_>> {
+    s.split_whitespace().map(int).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scan::{int, ints, ints_in_bytes, whitespace_ints, ScanError, ScanErrorKind};
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(Ok(vec![12_u32, 3, 45]), ints("a12b-3, 45!"));
+        assert_eq!(Ok(vec![12_i32, -3, 45]), ints("a12b-3, 45!"));
+        assert_eq!(Ok(vec![i8::MIN, i8::MAX]), ints("-128 127"));
+        assert_eq!(Ok(vec![5_u8, 3]), ints_in_bytes(b"5-3"));
+        assert_eq!(Ok(Vec::<u64>::new()), ints("no numbers"));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let line = "seeds: 12 4294967296";
+        let e = ints::<u32>(line).unwrap_err();
+        assert_eq!(ScanErrorKind::Overflow { type_name: "u32" }, e.kind);
+        assert_eq!("4294967296", e.text);
+
+        let e = e.in_line(line);
+        assert_eq!(11, e.column);
+        assert_eq!(
+            "expected a number that fits in u32, found `4294967296`",
+            e.to_string()
+        );
+
+        let e = ints_in_bytes::<i8>(b"1 -129").unwrap_err();
+        assert_eq!("-129", e.text);
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(Ok(42_u16), int("+42"));
+        assert_eq!(Ok(-42_i16), int("-42"));
+        assert_eq!(Ok(u64::MAX), int("18446744073709551615"));
+
+        for token in ["-42", "", "+", "4x2", " 42"] {
+            let e = int::<u16>(token).unwrap_err();
+            assert_eq!(ScanErrorKind::NotAnInteger, e.kind, "{token}");
+        }
+    }
+
+    #[test]
+    fn test_whitespace_ints() {
+        assert_eq!(Ok(vec![7_u64, 15, 30]), whitespace_ints("  7  15\t30 "));
+
+        let line = "41 48 x3 86";
+        let e: ScanError = whitespace_ints::<u32>(line).unwrap_err();
+        assert_eq!("`x3` is not a number", e.to_string());
+        assert_eq!(7, e.in_line(line).column);
+    }
+}
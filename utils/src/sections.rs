@@ -0,0 +1,225 @@
+use crate::{LineReader, ParseError, Trim};
+
+/// A block of lines, between blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// Name of the `name:` header, when read with headers
+    pub name: Option<&'a str>,
+    /// What follows the colon on the header line, trimmed, as the numbers of
+    /// `seeds: 79 14`
+    pub rest: &'a str,
+    /// Lines after the header, with their 1-based line number and without
+    /// trailing whitespace
+    pub lines: Vec<(usize, &'a str)>,
+    /// First line of the block, header or not, with its line number
+    first: (usize, &'a str),
+}
+
+impl<'a> Section<'a> {
+    /// The first line of the section, its header if it has one, with its
+    /// 1-based line number.
+    pub fn first_line(&self) -> (usize, &'a str) {
+        self.first
+    }
+
+    /// Error on the first line of the section, its header if it has one.
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        let (number, line) = self.first;
+        ParseError::in_line(line, line, expected).on_line(number)
+    }
+}
+
+/// An input split into sections on blank lines:
+///
+/// ```
+/// let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n";
+/// let sections = utils::Sections::named(input).unwrap();
+///
+/// assert_eq!("79 14", sections.at(0, "seeds").unwrap().rest);
+/// assert_eq!(2, sections.get("seed-to-soil map").unwrap().lines.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sections<'a> {
+    input: &'a str,
+    sections: Vec<Section<'a>>,
+}
+
+impl<'a> Sections<'a> {
+    /// Sections without headers, all of their lines being in [`Section::lines`].
+    pub fn new(input: &'a str) -> Self {
+        let sections = blocks(input)
+            .into_iter()
+            .map(|lines| Section {
+                name: None,
+                rest: "",
+                first: lines[0],
+                lines,
+            })
+            .collect();
+
+        Sections { input, sections }
+    }
+
+    /// Sections that each start with a `name:` header line.
+    pub fn named(input: &'a str) -> Result<Self, ParseError> {
+        let sections = blocks(input)
+            .into_iter()
+            .map(|mut lines| {
+                let (number, header) = lines.remove(0);
+                let (name, rest) = header
+                    .split_once(':')
+                    .filter(|(name, _)| !name.trim().is_empty())
+                    .ok_or_else(|| {
+                        ParseError::in_line(header, header, "a `name:` header").on_line(number)
+                    })?;
+
+                Ok(Section {
+                    name: Some(name.trim()),
+                    rest: rest.trim(),
+                    lines,
+                    first: (number, header),
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        Ok(Sections { input, sections })
+    }
+
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Sections in the order of the input.
+    pub fn iter(&self) -> impl Iterator<Item = &Section<'a>> {
+        self.sections.iter()
+    }
+
+    /// The first section called `name`, wherever it is.
+    pub fn get(&self, name: &str) -> Result<&Section<'a>, ParseError> {
+        self.sections
+            .iter()
+            .find(|section| section.name == Some(name))
+            .ok_or_else(|| ParseError::at_end(self.input, format!("a `{name}:` section")))
+    }
+
+    /// The section at `index`, which must be called `name`.
+    pub fn at(&self, index: usize, name: &str) -> Result<&Section<'a>, ParseError> {
+        let expected = format!("`{name}:`");
+        match self.sections.get(index) {
+            None => Err(ParseError::at_end(self.input, expected)),
+            Some(section) if section.name != Some(name) => Err(section.error(expected)),
+            Some(section) => Ok(section),
+        }
+    }
+
+    /// Check that every section is called one of `names`, pointing at the
+    /// name of the first one that isn't.
+    pub fn only(&self, names: &[&str]) -> Result<(), ParseError> {
+        let unknown = self
+            .sections
+            .iter()
+            .find(|section| !section.name.is_some_and(|name| names.contains(&name)));
+
+        match unknown {
+            None => Ok(()),
+            Some(section) => {
+                let known: Vec<_> = names.iter().map(|name| format!("`{name}:`")).collect();
+                let expected = format!("one of {0}", known.join(", "));
+                let (number, line) = section.first;
+                let text = section.name.unwrap_or(line);
+                Err(ParseError::in_line(line, text, expected).on_line(number))
+            }
+        }
+    }
+}
+
+/// Numbered non-blank lines, grouped by the blank lines between them.
+fn blocks(input: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut blocks = vec![];
+    let mut block = vec![];
+
+    for (number, line) in LineReader::new().trim(Trim::End).numbered(input) {
+        if !line.is_empty() {
+            block.push((number, line));
+        } else if !block.is_empty() {
+            blocks.push(std::mem::take(&mut block));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sections;
+
+    const INPUT: &str = "seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+
+soil-to-fertilizer map:
+0 15 37
+";
+
+    #[test]
+    fn test_named() {
+        let sections = Sections::named(INPUT).unwrap();
+        assert_eq!(3, sections.len());
+
+        let names: Vec<_> = sections.iter().map(|s| s.name.unwrap()).collect();
+        assert_eq!(
+            vec!["seeds", "seed-to-soil map", "soil-to-fertilizer map"],
+            names
+        );
+
+        let seeds = sections.at(0, "seeds").unwrap();
+        assert_eq!(("79 14", 0), (seeds.rest, seeds.lines.len()));
+
+        let map = sections.get("soil-to-fertilizer map").unwrap();
+        assert_eq!(vec![(9, "0 15 37")], map.lines);
+    }
+
+    #[test]
+    fn test_unnamed() {
+        let sections = Sections::new("a\nb  \n  \nc\n");
+        let blocks: Vec<_> = sections.iter().map(|s| s.lines.clone()).collect();
+        assert_eq!(vec![vec![(1, "a"), (2, "b")], vec![(4, "c")]], blocks);
+        assert!(Sections::new("\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let sections = Sections::named(INPUT).unwrap();
+
+        let e = sections.at(2, "fertilizer-to-water map").unwrap_err();
+        assert_eq!((8, 1), (e.line, e.column));
+        assert_eq!("`fertilizer-to-water map:`", e.expected);
+
+        let e = sections.at(3, "fertilizer-to-water map").unwrap_err();
+        assert_eq!(9, e.line);
+        assert_eq!("", e.text);
+
+        let e = sections.get("water-to-light map").unwrap_err();
+        assert_eq!("expected a `water-to-light map:` section", e.to_string());
+
+        let e = sections.only(&["seeds", "seed-to-soil map"]).unwrap_err();
+        assert_eq!((8, 1), (e.line, e.column));
+        assert_eq!("soil-to-fertilizer map", e.text);
+        assert!(sections
+            .only(&["seeds", "seed-to-soil map", "soil-to-fertilizer map"])
+            .is_ok());
+
+        let e = Sections::named("seeds: 1\n\n1 2 3\n").unwrap_err();
+        assert_eq!((3, "a `name:` header"), (e.line, e.expected.as_str()));
+    }
+}
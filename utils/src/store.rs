@@ -0,0 +1,84 @@
+//! TOML files keyed by day and part, such as the answer store and the
+//! submission history, which are edited in place so that their comments and
+//! formatting are kept.
+
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table};
+
+/// Load the document at `path`. A missing file is an empty document.
+pub fn load_doc(path: &Path) -> Result<DocumentMut> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => content
+            .parse::<DocumentMut>()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(DocumentMut::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// The `dayN.partM` item, if there is one.
+pub fn day_part(doc: &DocumentMut, day: u32, part: u8) -> Option<&Item> {
+    doc.get(&format!("day{day}"))?.get(format!("part{part}"))
+}
+
+/// The `dayN.partM` item, set to `default` if there is none yet. Fails if
+/// `dayN` is something else than a table.
+pub fn day_part_entry(
+    doc: &mut DocumentMut,
+    day: u32,
+    part: u8,
+    default: Item,
+) -> Result<&mut Item> {
+    // `[dayN.partM]` headers, without an empty `[dayN]` one
+    let mut implicit = Table::new();
+    implicit.set_implicit(true);
+
+    let key = format!("day{day}");
+    let day = doc
+        .entry(&key)
+        .or_insert(Item::Table(implicit))
+        .as_table_like_mut()
+        .ok_or_else(|| invalid(&key, "a table"))?;
+
+    Ok(day.entry(&format!("part{part}")).or_insert(default))
+}
+
+/// Error for a `dayN.partM` item that isn't what it should be.
+pub fn invalid_entry(day: u32, part: u8, expected: &str) -> Error {
+    invalid(&format!("day{day}.part{part}"), expected)
+}
+
+fn invalid(key: &str, expected: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("`{key}` is not {expected}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{day_part, day_part_entry, load_doc};
+    use toml_edit::{value, DocumentMut, Item, Table};
+
+    #[test]
+    fn test_day_part_entry() {
+        let mut doc: DocumentMut = "# Comment\n[day1.part1]\nexample = 1\n".parse().unwrap();
+        day_part_entry(&mut doc, 1, 2, Item::Table(Table::new())).unwrap()["example"] = value(2);
+        day_part_entry(&mut doc, 1, 1, Item::None).unwrap()["puzzle"] = value(3);
+
+        assert_eq!(
+            "# Comment\n[day1.part1]\nexample = 1\npuzzle = 3\n\n[day1.part2]\nexample = 2\n",
+            doc.to_string()
+        );
+        assert!(day_part(&doc, 1, 2).is_some());
+        assert!(day_part(&doc, 2, 1).is_none());
+
+        let mut doc: DocumentMut = "day1 = 5\n".parse().unwrap();
+        let e = day_part_entry(&mut doc, 1, 1, Item::None).unwrap_err();
+        assert_eq!("`day1` is not a table", e.to_string());
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let doc = load_doc("this/file/does/not/exist.toml".as_ref()).unwrap();
+        assert!(doc.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::json_string;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Whether spans are being recorded; checked first so that spans cost next to
+/// nothing otherwise.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static START: OnceLock<Instant> = OnceLock::new();
+static EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(vec![]);
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Small number identifying the current thread in the trace.
+fn thread_number() -> u64 {
+    THREAD.with(|id| {
+        if id.get() == 0 {
+            id.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+        }
+        id.get()
+    })
+}
+
+/// A finished span.
+#[derive(Debug, Clone)]
+pub struct TraceEvent {
+    pub name: Cow<'static, str>,
+    pub thread: u64,
+    /// Since tracing started
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+/// Times the code until it is dropped, when tracing is on:
+///
+/// ```
+/// let _s = utils::span("parse");
+/// ```
+#[must_use = "the span ends as soon as it is dropped"]
+pub struct Span {
+    name: Cow<'static, str>,
+    start: Option<Instant>,
+}
+
+pub fn span(name: impl Into<Cow<'static, str>>) -> Span {
+    span_with(|| name)
+}
+
+/// A span whose name is only built when tracing is on, for names that need
+/// formatting:
+///
+/// ```
+/// let part = 2;
+/// let _s = utils::span_with(|| format!("part{part}"));
+/// ```
+pub fn span_with<N: Into<Cow<'static, str>>>(name: impl FnOnce() -> N) -> Span {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return Span {
+            name: Cow::Borrowed(""),
+            start: None,
+        };
+    }
+
+    Span {
+        name: name().into(),
+        start: Some(Instant::now()),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let duration = start.elapsed();
+        let origin = *START.get_or_init(Instant::now);
+
+        let event = TraceEvent {
+            name: std::mem::take(&mut self.name),
+            thread: thread_number(),
+            start: start.saturating_duration_since(origin),
+            duration,
+        };
+        EVENTS.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+    }
+}
+
+/// Start recording spans.
+pub fn start_trace() {
+    START.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stop recording spans and hand back the ones recorded so far, by start time.
+pub fn finish_trace() -> Vec<TraceEvent> {
+    ENABLED.store(false, Ordering::Relaxed);
+    let mut events = std::mem::take(&mut *EVENTS.lock().unwrap_or_else(|e| e.into_inner()));
+    events.sort_by_key(|event| event.start);
+    events
+}
+
+/// Events in the Chrome trace event format, as complete (`X`) events with
+/// microsecond timestamps, for `chrome://tracing`, Perfetto or speedscope.
+pub fn chrome_trace(events: &[TraceEvent]) -> String {
+    let events: Vec<String> = events
+        .iter()
+        .map(|event| {
+            format!(
+                "{{\"name\": {0}, \"ph\": \"X\", \"ts\": {1:.3}, \"dur\": {2:.3}, \"pid\": 1, \"tid\": {3}}}",
+                json_string(&event.name),
+                event.start.as_nanos() as f64 / 1000.0,
+                event.duration.as_nanos() as f64 / 1000.0,
+                event.thread
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"traceEvents\": [\n{0}\n], \"displayTimeUnit\": \"ms\"}}\n",
+        events.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::{chrome_trace, finish_trace, span, span_with, start_trace, TraceEvent};
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    #[test]
+    fn test_chrome_trace() {
+        let events = [TraceEvent {
+            name: Cow::Borrowed("day5 \"part2\""),
+            thread: 2,
+            start: Duration::from_nanos(1500),
+            duration: Duration::from_micros(20),
+        }];
+
+        let expected = r#"{"traceEvents": [
+{"name": "day5 \"part2\"", "ph": "X", "ts": 1.500, "dur": 20.000, "pid": 1, "tid": 2}
+], "displayTimeUnit": "ms"}
+"#;
+        assert_eq!(expected, chrome_trace(&events));
+    }
+
+    #[test]
+    fn test_span() {
+        {
+            let _s = span("not traced");
+            let _f = span_with(|| -> String { panic!("Named while tracing is off") });
+        }
+        start_trace();
+        {
+            let _outer = span("outer");
+            let _inner = span_with(|| format!("inner {0}", 1));
+        }
+        let events = finish_trace();
+
+        let names: Vec<_> = events.iter().map(|e| e.name.as_ref()).collect();
+        assert!(!names.contains(&"not traced"));
+        let outer = events.iter().find(|e| e.name == "outer").unwrap();
+        let inner = events.iter().find(|e| e.name == "inner 1").unwrap();
+        assert!(outer.start <= inner.start && inner.duration <= outer.duration);
+    }
+}